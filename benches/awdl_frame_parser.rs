@@ -1,9 +1,14 @@
 use awdl_frame_parser::{
     action_frame::DefaultAWDLActionFrame,
     tlvs::{
-        data_path::DataPathStateTLV,
+        data_path::{DataPathStateTLV, HTCapabilitiesTLV},
         dns_sd::{DefaultArpaTLV, DefaultServiceResponseTLV},
-        sync_elect::{ChannelSequenceTLV, DefaultSyncTreeTLV},
+        sync_elect::{
+            ChannelSequenceTLV, DefaultSyncTreeTLV, ElectionParametersTLV, ElectionParametersV2TLV,
+            SynchronizationParametersTLV,
+        },
+        version::VersionTLV,
+        ReadTLVs,
     },
 };
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
@@ -70,11 +75,37 @@ bench_write!(bench_write_data_path_state_tlv, DataPathStateTLV, 0xff);
 bench_read!(bench_read_arpa_tlv, DefaultArpaTLV);
 bench_write!(bench_write_arpa_tlv, DefaultArpaTLV, 0xff);
 
+/// Look up the TLVs a peer tracker typically needs, by rescanning the TLVs every time.
+fn bench_get_first_tlvs(tlvs: ReadTLVs) {
+    black_box(tlvs.get_first_tlv::<SynchronizationParametersTLV>());
+    black_box(tlvs.get_first_tlv::<ElectionParametersTLV>());
+    black_box(tlvs.get_first_tlv::<ChannelSequenceTLV>());
+    black_box(tlvs.get_first_tlv::<ElectionParametersV2TLV>());
+    black_box(tlvs.get_first_tlv::<DataPathStateTLV>());
+    black_box(tlvs.get_first_tlv::<HTCapabilitiesTLV>());
+    black_box(tlvs.get_first_tlv::<DefaultArpaTLV>());
+    black_box(tlvs.get_first_tlv::<VersionTLV>());
+}
+/// Look up the same TLVs as [bench_get_first_tlvs], but through the index.
+fn bench_get_indexed_tlvs(tlvs: ReadTLVs) {
+    let index = tlvs.index();
+    black_box(index.get_first_tlv::<SynchronizationParametersTLV>());
+    black_box(index.get_first_tlv::<ElectionParametersTLV>());
+    black_box(index.get_first_tlv::<ChannelSequenceTLV>());
+    black_box(index.get_first_tlv::<ElectionParametersV2TLV>());
+    black_box(index.get_first_tlv::<DataPathStateTLV>());
+    black_box(index.get_first_tlv::<HTCapabilitiesTLV>());
+    black_box(index.get_first_tlv::<DefaultArpaTLV>());
+    black_box(index.get_first_tlv::<VersionTLV>());
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let af_bytes = include_bytes!("../test_bins/mif.bin");
     register_bench_fn!(c, bench_read_af, af_bytes);
     let af = af_bytes.pread::<DefaultAWDLActionFrame>(0).unwrap();
     register_bench_fn!(c, bench_write_af, af.clone());
+    register_bench_fn!(c, bench_get_first_tlvs, af.tagged_data);
+    register_bench_fn!(c, bench_get_indexed_tlvs, af.tagged_data);
 
     let data_path_state_tlv_bytes = &include_bytes!("../test_bins/data_path_state_tlv.bin")[3..];
     register_bench_fn!(c, bench_read_data_path_state_tlv, data_path_state_tlv_bytes);
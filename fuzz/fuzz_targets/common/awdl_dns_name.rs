@@ -1,9 +1,11 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use bin_utils::*;
+use scroll::Pread;
 extern crate awdl_frame_parser;
 
+use awdl_frame_parser::common::DefaultAWDLDnsName;
+
 fuzz_target!(|data: &[u8]| {
-    let _ = awdl_frame_parser::common::AWDLDnsName::from_bytes(&mut data.iter().copied());
+    let _ = data.pread::<DefaultAWDLDnsName>(0);
 });
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scroll::{ctx::MeasureWith, Pread, Pwrite};
+extern crate awdl_frame_parser;
+
+use awdl_frame_parser::{
+    common::ReadLabelIterator, tlvs::dns_sd::dns_record::AWDLDnsRecord,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(record) = data.pread::<AWDLDnsRecord<ReadLabelIterator>>(0) else {
+        return;
+    };
+    let _ = record.txt_entries().map(|entries| entries.count());
+    let mut buf = vec![0x00; record.measure_with(&())];
+    if let Ok(length) = buf.pwrite(record, 0) {
+        let _ = buf[..length].pread::<AWDLDnsRecord<ReadLabelIterator>>(0);
+    }
+});
@@ -1,9 +1,18 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use bin_utils::*;
+use scroll::{ctx::MeasureWith, Pread, Pwrite};
 extern crate awdl_frame_parser;
 
-fuzz_target!(|data:&[u8]| {
-    let _ = awdl_frame_parser::tlvs::dns_sd::ServiceResponseTLV::from_bytes(&mut data.iter().copied());
+use awdl_frame_parser::tlvs::dns_sd::DefaultServiceResponseTLV;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(service_response_tlv) = data.pread::<DefaultServiceResponseTLV>(0) else {
+        return;
+    };
+    // Everything, that was read, has to be writable again.
+    let mut buf = vec![0x00; service_response_tlv.measure_with(&())];
+    if let Ok(length) = buf.pwrite(service_response_tlv, 0) {
+        let _ = buf[..length].pread::<DefaultServiceResponseTLV>(0);
+    }
 });
@@ -0,0 +1,227 @@
+use core::{fmt::Debug, time::Duration};
+
+use heapless::Vec;
+use mac_parser::MACAddress;
+use scroll::{
+    ctx::{MeasureWith, TryIntoCtx},
+    Pwrite,
+};
+
+use crate::{common::AWDLStr, tlvs::AWDLTLV};
+
+use super::{AWDLActionFrame, AWDLActionFrameSubType, ValidatedTLVs};
+
+/// The TLVs collected by an [ActionFrameBuilder].
+struct FixedTLVs<'a, MACIterator, LabelIterator, const N: usize>(
+    Vec<AWDLTLV<'a, MACIterator, LabelIterator>, N>,
+);
+impl<'a, MACIterator, LabelIterator, const N: usize> MeasureWith<()>
+    for FixedTLVs<'a, MACIterator, LabelIterator, N>
+where
+    MACIterator: ExactSizeIterator,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone + Debug,
+{
+    fn measure_with(&self, ctx: &()) -> usize {
+        self.0.iter().map(|tlv| tlv.measure_with(ctx)).sum()
+    }
+}
+impl<'a, MACIterator, LabelIterator, const N: usize> TryIntoCtx
+    for FixedTLVs<'a, MACIterator, LabelIterator, N>
+where
+    MACIterator: IntoIterator<Item = MACAddress> + ExactSizeIterator + Clone,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+{
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+        for tlv in self.0 {
+            buf.gwrite(tlv, &mut offset)?;
+        }
+        Ok(offset)
+    }
+}
+
+/// The validated action frame returned by [ActionFrameBuilder::validate].
+pub type ValidatedActionFrame<'a, MACIterator, LabelIterator, const N: usize> =
+    AWDLActionFrame<ValidatedTLVs<Vec<AWDLTLV<'a, MACIterator, LabelIterator>, N>>>;
+
+/// A builder for [action frames](AWDLActionFrame), which collects up to `N` TLVs without allocating.
+///
+/// The frame is written into a caller supplied buffer, which makes it suitable for emitting PSFs and MIFs on targets without an allocator.
+pub struct ActionFrameBuilder<'a, MACIterator, LabelIterator, const N: usize> {
+    subtype: AWDLActionFrameSubType,
+    phy_tx_time: Duration,
+    target_tx_time: Duration,
+    tlvs: Vec<AWDLTLV<'a, MACIterator, LabelIterator>, N>,
+}
+impl<'a, MACIterator, LabelIterator, const N: usize>
+    ActionFrameBuilder<'a, MACIterator, LabelIterator, N>
+{
+    /// Create a new builder without any TLVs.
+    pub const fn new(
+        subtype: AWDLActionFrameSubType,
+        phy_tx_time: Duration,
+        target_tx_time: Duration,
+    ) -> Self {
+        Self {
+            subtype,
+            phy_tx_time,
+            target_tx_time,
+            tlvs: Vec::new(),
+        }
+    }
+    /// Append a TLV to the frame.
+    ///
+    /// This fails, if the builder already holds `N` TLVs.
+    pub fn push_tlv(
+        &mut self,
+        tlv: impl Into<AWDLTLV<'a, MACIterator, LabelIterator>>,
+    ) -> Result<(), scroll::Error> {
+        self.tlvs
+            .push(tlv.into())
+            .map_err(|_| scroll::Error::TooBig {
+                size: N + 1,
+                len: N,
+            })
+    }
+    /// Append a TLV to the frame and return the builder.
+    pub fn with_tlv(
+        mut self,
+        tlv: impl Into<AWDLTLV<'a, MACIterator, LabelIterator>>,
+    ) -> Result<Self, scroll::Error> {
+        self.push_tlv(tlv)?;
+        Ok(self)
+    }
+    /// The TLVs collected so far.
+    pub fn tlvs(&self) -> &[AWDLTLV<'a, MACIterator, LabelIterator>] {
+        &self.tlvs
+    }
+}
+impl<'a, MACIterator, LabelIterator, const N: usize>
+    ActionFrameBuilder<'a, MACIterator, LabelIterator, N>
+where
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+    MACIterator: IntoIterator<Item = MACAddress> + Clone,
+{
+    /// Validate the collected TLVs against the rules for the subtype.
+    ///
+    /// The returned frame writes the TLVs in the [canonical order](super::CANONICAL_TLV_ORDER).
+    pub fn validate(
+        self,
+    ) -> Result<ValidatedActionFrame<'a, MACIterator, LabelIterator, N>, scroll::Error> {
+        AWDLActionFrame {
+            subtype: self.subtype,
+            phy_tx_time: self.phy_tx_time,
+            target_tx_time: self.target_tx_time,
+            tagged_data: self.tlvs,
+        }
+        .validate()
+    }
+}
+impl<'a, MACIterator, LabelIterator, const N: usize> MeasureWith<()>
+    for ActionFrameBuilder<'a, MACIterator, LabelIterator, N>
+where
+    MACIterator: ExactSizeIterator,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone + Debug,
+{
+    fn measure_with(&self, ctx: &()) -> usize {
+        12 + self
+            .tlvs
+            .iter()
+            .map(|tlv| tlv.measure_with(ctx))
+            .sum::<usize>()
+    }
+}
+impl<'a, MACIterator, LabelIterator, const N: usize> TryIntoCtx
+    for ActionFrameBuilder<'a, MACIterator, LabelIterator, N>
+where
+    MACIterator: IntoIterator<Item = MACAddress> + ExactSizeIterator + Clone,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone + Debug,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+{
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        // Checking this up front, prevents us from writing half a frame.
+        let length = self.measure_with(&());
+        if buf.len() < length {
+            return Err(scroll::Error::TooBig {
+                size: length,
+                len: buf.len(),
+            });
+        }
+        buf.pwrite(
+            AWDLActionFrame {
+                subtype: self.subtype,
+                phy_tx_time: self.phy_tx_time,
+                target_tx_time: self.target_tx_time,
+                tagged_data: FixedTLVs(self.tlvs),
+            },
+            0,
+        )
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_action_frame_builder() {
+    use scroll::Pread;
+
+    use crate::{
+        common::ReadLabelIterator,
+        tlvs::{sync_elect::ReadMACIterator, version::VersionTLV, DefaultAWDLTLV},
+    };
+
+    let bytes = include_bytes!("../../test_bins/mif.bin");
+    let parsed_af = bytes.pread::<super::DefaultAWDLActionFrame>(0).unwrap();
+
+    let mut builder = ActionFrameBuilder::<_, _, 16>::new(
+        parsed_af.subtype,
+        parsed_af.phy_tx_time,
+        parsed_af.target_tx_time,
+    );
+    let mut offset = 12;
+    while offset < bytes.len() {
+        builder
+            .push_tlv(bytes.gread::<DefaultAWDLTLV>(&mut offset).unwrap())
+            .unwrap();
+    }
+
+    let mut buf = [0x00u8; 0x200];
+    assert_eq!(builder.measure_with(&()), bytes.len());
+    let length = buf.pwrite(builder, 0).unwrap();
+    assert_eq!(&buf[..length], bytes.as_slice());
+
+    let mut builder = ActionFrameBuilder::<_, _, 16>::new(
+        parsed_af.subtype,
+        parsed_af.phy_tx_time,
+        parsed_af.target_tx_time,
+    );
+    let mut offset = 12;
+    while offset < bytes.len() {
+        builder
+            .push_tlv(bytes.gread::<DefaultAWDLTLV>(&mut offset).unwrap())
+            .unwrap();
+    }
+    let length = buf.pwrite(builder.validate().unwrap(), 0).unwrap();
+    assert_eq!(&buf[..length], bytes.as_slice());
+
+    let mut builder = ActionFrameBuilder::<ReadMACIterator, ReadLabelIterator, 1>::new(
+        AWDLActionFrameSubType::PSF,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+    let version_tlv = VersionTLV::default();
+    builder.push_tlv(version_tlv).unwrap();
+    assert!(builder.push_tlv(version_tlv).is_err());
+    assert!(buf[..14].pwrite(builder, 0).is_err());
+
+    let builder = ActionFrameBuilder::<ReadMACIterator, ReadLabelIterator, 1>::new(
+        AWDLActionFrameSubType::PSF,
+        Duration::ZERO,
+        Duration::ZERO,
+    )
+    .with_tlv(version_tlv)
+    .unwrap();
+    assert!(builder.validate().is_err());
+}
@@ -1,3 +1,7 @@
+#[cfg(feature = "heapless")]
+mod builder;
+mod validation;
+
 use macro_bits::serializable_enum;
 use scroll::{
     ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
@@ -8,6 +12,10 @@ use core::{fmt::Debug, time::Duration};
 
 use crate::tlvs::{ReadTLVs, AWDLTLV};
 
+#[cfg(feature = "heapless")]
+pub use builder::{ActionFrameBuilder, ValidatedActionFrame};
+pub use validation::{ValidatedTLVs, CANONICAL_TLV_ORDER};
+
 serializable_enum! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     pub enum AWDLActionFrameSubType: u8 {
@@ -128,7 +136,7 @@ pub type DefaultAWDLActionFrame<'a> = AWDLActionFrame<ReadTLVs<'a>>;
 fn test_action_frame() {
     use alloc::vec;
 
-    let packet_bytes = include_bytes!("../test_bins/mif.bin");
+    let packet_bytes = include_bytes!("../../test_bins/mif.bin");
     let parsed_af = packet_bytes.pread::<DefaultAWDLActionFrame>(0).unwrap();
     //panic!("{parsed_af:#?}");
     let mut buf = vec![0; parsed_af.measure_with(&())];
@@ -0,0 +1,279 @@
+use core::fmt::Debug;
+
+use mac_parser::MACAddress;
+use scroll::{
+    ctx::{MeasureWith, TryIntoCtx},
+    Pwrite,
+};
+
+use crate::{
+    common::AWDLStr,
+    tlvs::{AWDLTLVType, AWDLTLV},
+};
+
+use super::{AWDLActionFrame, AWDLActionFrameSubType};
+
+/// The order in which macOS emits the TLVs.
+///
+/// TLVs not listed here are written after all listed ones.
+pub const CANONICAL_TLV_ORDER: [AWDLTLVType; 12] = [
+    AWDLTLVType::SynchronizationParameters,
+    AWDLTLVType::ElectionParameters,
+    AWDLTLVType::ChannelSequence,
+    AWDLTLVType::ElectionParametersV2,
+    AWDLTLVType::SynchronizationTree,
+    AWDLTLVType::DataPathState,
+    AWDLTLVType::HTCapabilities,
+    AWDLTLVType::IEEE80211Container,
+    AWDLTLVType::Arpa,
+    AWDLTLVType::Version,
+    // The service parameters.
+    AWDLTLVType::Unknown(0x06),
+    AWDLTLVType::ServiceResponse,
+];
+/// Returns the position of the TLV type in the [canonical order](CANONICAL_TLV_ORDER).
+fn canonical_position(tlv_type: AWDLTLVType) -> usize {
+    CANONICAL_TLV_ORDER
+        .iter()
+        .position(|canonical_type| *canonical_type == tlv_type)
+        .unwrap_or(CANONICAL_TLV_ORDER.len())
+}
+
+impl AWDLActionFrameSubType {
+    /// The TLVs, which have to be present exactly once in an action frame of this subtype.
+    pub const fn mandatory_tlvs(&self) -> &'static [AWDLTLVType] {
+        match self {
+            Self::PSF | Self::MIF => &[
+                AWDLTLVType::SynchronizationParameters,
+                AWDLTLVType::ElectionParameters,
+                AWDLTLVType::ChannelSequence,
+                AWDLTLVType::ElectionParametersV2,
+                AWDLTLVType::DataPathState,
+                AWDLTLVType::Version,
+            ],
+            Self::Unknown(_) => &[],
+        }
+    }
+    /// The TLVs, which may be present in an action frame of this subtype.
+    ///
+    /// Unknown TLV types are always permitted.
+    pub const fn optional_tlvs(&self) -> &'static [AWDLTLVType] {
+        match self {
+            Self::PSF => &[
+                AWDLTLVType::SynchronizationTree,
+                AWDLTLVType::HTCapabilities,
+                AWDLTLVType::IEEE80211Container,
+            ],
+            Self::MIF => &[
+                AWDLTLVType::SynchronizationTree,
+                AWDLTLVType::HTCapabilities,
+                AWDLTLVType::IEEE80211Container,
+                AWDLTLVType::Arpa,
+                AWDLTLVType::Unknown(0x06),
+                AWDLTLVType::ServiceResponse,
+            ],
+            Self::Unknown(_) => &[],
+        }
+    }
+    /// Check if the TLV types match the rules for this subtype.
+    ///
+    /// Mandatory TLVs have to be present exactly once, optional ones at most once, with the exception of service responses.
+    pub fn validate_tlv_types(
+        &self,
+        tlv_types: impl IntoIterator<Item = AWDLTLVType>,
+    ) -> Result<(), scroll::Error> {
+        if let Self::Unknown(_) = self {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Unknown action frame subtype.",
+            });
+        }
+        let mut counts = [0usize; CANONICAL_TLV_ORDER.len()];
+        for tlv_type in tlv_types {
+            let position = canonical_position(tlv_type);
+            if position == CANONICAL_TLV_ORDER.len() {
+                continue;
+            }
+            if !self.mandatory_tlvs().contains(&tlv_type)
+                && !self.optional_tlvs().contains(&tlv_type)
+            {
+                return Err(scroll::Error::BadInput {
+                    size: 0,
+                    msg: "TLV isn't permitted in this action frame subtype.",
+                });
+            }
+            counts[position] += 1;
+            if counts[position] > 1 && tlv_type != AWDLTLVType::ServiceResponse {
+                return Err(scroll::Error::BadInput {
+                    size: 0,
+                    msg: "TLV is present more than once.",
+                });
+            }
+        }
+        if self
+            .mandatory_tlvs()
+            .iter()
+            .any(|tlv_type| counts[canonical_position(*tlv_type)] == 0)
+        {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Mandatory TLV is missing.",
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// TLVs, which were validated against the rules for an [action frame subtype](AWDLActionFrameSubType).
+///
+/// When written, the TLVs are reordered into the [canonical order](CANONICAL_TLV_ORDER), while TLVs of the same type keep their relative order.
+pub struct ValidatedTLVs<I> {
+    tlvs: I,
+}
+impl<'a, I, MACIterator, LabelIterator> ValidatedTLVs<I>
+where
+    I: IntoIterator<Item = AWDLTLV<'a, MACIterator, LabelIterator>> + Clone,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+    MACIterator: IntoIterator<Item = MACAddress> + Clone,
+{
+    /// Validate the TLVs against the rules for the subtype.
+    pub fn new(subtype: AWDLActionFrameSubType, tlvs: I) -> Result<Self, scroll::Error> {
+        subtype.validate_tlv_types(tlvs.clone().into_iter().map(|tlv| tlv.get_type()))?;
+        Ok(Self { tlvs })
+    }
+    /// Returns the validated TLVs in their original order.
+    pub fn into_inner(self) -> I {
+        self.tlvs
+    }
+}
+impl<'a, I, MACIterator, LabelIterator> MeasureWith<()> for ValidatedTLVs<I>
+where
+    I: IntoIterator<Item = AWDLTLV<'a, MACIterator, LabelIterator>> + Clone,
+    MACIterator: ExactSizeIterator,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone + Debug,
+{
+    fn measure_with(&self, ctx: &()) -> usize {
+        self.tlvs
+            .clone()
+            .into_iter()
+            .map(|tlv| tlv.measure_with(ctx))
+            .sum()
+    }
+}
+impl<'a, I, MACIterator, LabelIterator> TryIntoCtx for ValidatedTLVs<I>
+where
+    I: IntoIterator<Item = AWDLTLV<'a, MACIterator, LabelIterator>> + Clone,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+    MACIterator: IntoIterator<Item = MACAddress> + ExactSizeIterator + Clone,
+{
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+        // One pass per position avoids an allocation for sorting.
+        for position in 0..=CANONICAL_TLV_ORDER.len() {
+            for tlv in self.tlvs.clone() {
+                if canonical_position(tlv.get_type()) == position {
+                    buf.gwrite(tlv, &mut offset)?;
+                }
+            }
+        }
+        Ok(offset)
+    }
+}
+impl<'a, I, MACIterator, LabelIterator> AWDLActionFrame<I>
+where
+    I: IntoIterator<Item = AWDLTLV<'a, MACIterator, LabelIterator>> + Clone,
+    LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    <LabelIterator as IntoIterator>::IntoIter: Clone,
+    MACIterator: IntoIterator<Item = MACAddress> + Clone,
+{
+    /// Validate the TLVs against the rules for the subtype of the frame.
+    ///
+    /// The returned frame writes the TLVs in the [canonical order](CANONICAL_TLV_ORDER).
+    pub fn validate(self) -> Result<AWDLActionFrame<ValidatedTLVs<I>>, scroll::Error> {
+        Ok(AWDLActionFrame {
+            subtype: self.subtype,
+            phy_tx_time: self.phy_tx_time,
+            target_tx_time: self.target_tx_time,
+            tagged_data: ValidatedTLVs::new(self.subtype, self.tagged_data)?,
+        })
+    }
+}
+#[cfg(test)]
+mod validation_tests {
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    use scroll::{ctx::MeasureWith, Pread, Pwrite};
+
+    use crate::{
+        action_frame::{AWDLActionFrame, AWDLActionFrameSubType, DefaultAWDLActionFrame},
+        tlvs::{AWDLTLVType, DefaultAWDLTLV},
+    };
+
+    fn read_mif_tlvs(bytes: &[u8]) -> Vec<DefaultAWDLTLV<'_>> {
+        let mut offset = 12;
+        let mut tlvs = Vec::new();
+        while offset < bytes.len() {
+            tlvs.push(bytes.gread(&mut offset).unwrap());
+        }
+        tlvs
+    }
+    #[test]
+    fn test_tlv_type_validation() {
+        let bytes = include_bytes!("../../test_bins/mif.bin");
+        let tlv_types = read_mif_tlvs(bytes)
+            .iter()
+            .map(DefaultAWDLTLV::get_type)
+            .collect::<Vec<_>>();
+        assert!(AWDLActionFrameSubType::MIF
+            .validate_tlv_types(tlv_types.iter().copied())
+            .is_ok());
+        // PSFs don't carry service responses.
+        assert!(AWDLActionFrameSubType::PSF
+            .validate_tlv_types(tlv_types.iter().copied())
+            .is_err());
+        assert!(AWDLActionFrameSubType::MIF
+            .validate_tlv_types(
+                tlv_types
+                    .iter()
+                    .copied()
+                    .filter(|tlv_type| *tlv_type != AWDLTLVType::DataPathState)
+            )
+            .is_err());
+        assert!(AWDLActionFrameSubType::MIF
+            .validate_tlv_types(tlv_types.iter().copied().chain([AWDLTLVType::Version]))
+            .is_err());
+    }
+    #[test]
+    fn test_validated_tlvs_reorder() {
+        let bytes = include_bytes!("../../test_bins/mif.bin");
+        let parsed_af = bytes.pread::<DefaultAWDLActionFrame>(0).unwrap();
+        let mut tlvs = read_mif_tlvs(bytes);
+        tlvs.rotate_left(4);
+
+        let af = AWDLActionFrame {
+            subtype: parsed_af.subtype,
+            phy_tx_time: parsed_af.phy_tx_time,
+            target_tx_time: parsed_af.target_tx_time,
+            tagged_data: tlvs,
+        }
+        .validate()
+        .unwrap();
+        let mut buf = alloc::vec![0x00; af.measure_with(&())];
+        buf.pwrite(af, 0).unwrap();
+        assert_eq!(buf, bytes);
+
+        assert!(AWDLActionFrame {
+            subtype: AWDLActionFrameSubType::PSF,
+            phy_tx_time: Duration::ZERO,
+            target_tx_time: Duration::ZERO,
+            tagged_data: Vec::<DefaultAWDLTLV>::new(),
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -1,9 +1,16 @@
+use core::fmt::Display;
+
 use macro_bits::serializable_enum;
 
 serializable_enum! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     /// Compressed dns-sd domains/services. Compression might be the wrong word though.
+    ///
+    /// Only these values have been confirmed in captures, so the table isn't extended with guessed values.
+    /// Suffixes of newer services, like companion-link, Sidecar and remote pairing, are kept as [Unknown](AWDLDnsCompression::Unknown).
+    /// Names of those services can still be written with the [Null](AWDLDnsCompression::Null) compression, followed by their own labels.
     pub enum AWDLDnsCompression: u16 {
+        /// No compression, so the name ends with its own labels.
         Null => 0xC000,
 
         AirPlayTcpLocal => 0xC001,
@@ -37,15 +44,18 @@ serializable_enum! {
     }
 }
 impl AWDLDnsCompression {
-    pub fn to_static_string(&self) -> &'static str {
-        match self {
+    /// Returns the suffix, to which the compression expands.
+    ///
+    /// This returns [None] for [unknown](AWDLDnsCompression::Unknown) compressions.
+    pub const fn to_static_string(&self) -> Option<&'static str> {
+        Some(match self {
             AWDLDnsCompression::Null => "",
             AWDLDnsCompression::AirPlayTcpLocal => "_airplay._tcp.local",
             AWDLDnsCompression::AirPlayUdpLocal => "_airplay._udp.local",
             AWDLDnsCompression::AirPlay => "_airplay",
             AWDLDnsCompression::RaopTcpLocal => "_raop._tcp.local",
             AWDLDnsCompression::RaopUdpLocal => "_raop._udp.local",
-            AWDLDnsCompression::Raop => "raop",
+            AWDLDnsCompression::Raop => "_raop",
             AWDLDnsCompression::AirDropTcpLocal => "_airdrop._tcp.local",
             AWDLDnsCompression::AirDropUdpLocal => "_airdrop._udp.local",
             AWDLDnsCompression::AirDrop => "_airdrop",
@@ -54,7 +64,25 @@ impl AWDLDnsCompression {
             AWDLDnsCompression::Local => "local",
             AWDLDnsCompression::Ip6Arpa => "ip6.arpa",
             AWDLDnsCompression::Ip4Arpa => "ip4.arpa",
-            AWDLDnsCompression::Unknown(_) => "Unknown Compression",
+            AWDLDnsCompression::Unknown(_) => return None,
+        })
+    }
+}
+impl Display for AWDLDnsCompression {
+    /// Unknown compressions are written as their hexadecimal value in angle brackets, which can't be mistaken for a label.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.to_static_string() {
+            Some(suffix) => f.write_str(suffix),
+            None => write!(f, "<{:#06x}>", self.into_bits()),
         }
     }
 }
+#[cfg(test)]
+#[test]
+fn test_dns_compression_display() {
+    use alloc::string::ToString;
+
+    assert_eq!(AWDLDnsCompression::Raop.to_string(), "_raop");
+    assert_eq!(AWDLDnsCompression::Null.to_string(), "");
+    assert_eq!(AWDLDnsCompression::Unknown(0xc0ff).to_string(), "<0xc0ff>");
+}
@@ -1,5 +1,6 @@
 use core::{
     fmt::{Display, Write},
+    hash::{Hash, Hasher},
     iter::repeat,
 };
 use scroll::{
@@ -20,6 +21,10 @@ impl<'a> ReadLabelIterator<'a> {
     pub const fn new(bytes: &'a [u8]) -> Self {
         Self { bytes, offset: 0 }
     }
+    /// Returns the bytes, which haven't been read yet.
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
 }
 impl MeasureWith<()> for ReadLabelIterator<'_> {
     fn measure_with(&self, _ctx: &()) -> usize {
@@ -42,7 +47,7 @@ impl ExactSizeIterator for ReadLabelIterator<'_> {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Hash)]
+#[derive(Clone, Copy, Debug, Default)]
 /// A hostname combined with the [domain](AWDLDnsCompression).
 pub struct AWDLDnsName<I> {
     /// The labels of the peer.
@@ -58,7 +63,16 @@ where
     RhsIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
 {
     fn eq(&self, other: &AWDLDnsName<RhsIterator>) -> bool {
-        self.labels.clone().into_iter().eq(other.labels.clone())
+        self.domain == other.domain && self.labels.clone().into_iter().eq(other.labels.clone())
+    }
+}
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for AWDLDnsName<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Like the equality, this only depends on the domain and the labels and not on the iterator type.
+        self.domain.hash(state);
+        for label in self.labels.clone() {
+            label.hash(state);
+        }
     }
 }
 
@@ -79,7 +93,23 @@ impl<'a> TryFromCtx<'a> for AWDLDnsName<ReadLabelIterator<'a>> {
     type Error = scroll::Error;
     fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
         let mut offset = 0;
-        let label_bytes = from.gread_with(&mut offset, from.len() - 2)?;
+        // The compression occupies the last two bytes.
+        let label_length = from.len().checked_sub(2).ok_or(scroll::Error::TooBig {
+            size: 2,
+            len: from.len(),
+        })?;
+        let label_bytes = from.gread_with::<&[u8]>(&mut offset, label_length)?;
+        // The labels have to fill the bytes before the compression exactly.
+        let mut label_offset = 0;
+        while label_offset < label_length {
+            label_offset += 1 + label_bytes[label_offset] as usize;
+        }
+        if label_offset != label_length {
+            return Err(scroll::Error::BadInput {
+                size: label_offset,
+                msg: "DNS name labels exceed the name.",
+            });
+        }
         let domain =
             AWDLDnsCompression::from_bits(from.gread_with(&mut offset, NETWORK)?);
         Ok((
@@ -111,11 +141,14 @@ where
     I: IntoIterator<Item = AWDLStr<'a>> + Clone,
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        for label in self.labels.clone() {
-            f.write_str(&label)?;
-            f.write_char('.')?;
+        let mut labels = self.labels.clone().into_iter().peekable();
+        while let Some(label) = labels.next() {
+            label.fmt(f)?;
+            if labels.peek().is_some() || self.domain != AWDLDnsCompression::Null {
+                f.write_char('.')?;
+            }
         }
-        f.write_str(self.domain.to_static_string())
+        self.domain.fmt(f)
     }
 }
 /// The default awdl dns name returned by reading.
@@ -139,4 +172,62 @@ fn test_dns_name() {
     let mut buf = vec![0x00; dns_name.measure_with(&())];
     buf.pwrite(dns_name, 0).unwrap();
     assert_eq!(bytes, buf);
+
+    // Equal names have to hash equally, regardless of the iterator type.
+    #[derive(Default)]
+    struct ByteHasher(alloc::vec::Vec<u8>);
+    impl Hasher for ByteHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+    let mut read_hasher = ByteHasher::default();
+    dns_name.hash(&mut read_hasher);
+    let mut array_hasher = ByteHasher::default();
+    AWDLDnsName {
+        labels: ["awdl".into(), "awdl".into()],
+        domain: AWDLDnsCompression::Local,
+    }
+    .hash(&mut array_hasher);
+    assert_eq!(read_hasher.0, array_hasher.0);
+}
+#[cfg(test)]
+#[test]
+fn test_dns_name_non_utf8() {
+    use alloc::{string::ToString, vec};
+    let bytes = [0x05, b'M', 0xe4, b'x', b'.', b'1', 0xc0, 0x0c].as_slice();
+    let dns_name = bytes.pread::<DefaultAWDLDnsName>(0).unwrap();
+    assert_eq!(dns_name.labels.count(), 1);
+    assert_eq!(dns_name.to_string(), "M\\228x\\.1.local");
+    let mut buf = vec![0x00; dns_name.measure_with(&())];
+    buf.pwrite(dns_name, 0).unwrap();
+    assert_eq!(bytes, buf);
+}
+#[cfg(test)]
+#[test]
+fn test_dns_name_domain() {
+    use alloc::string::ToString;
+    let name = AWDLDnsName {
+        labels: ["simon".into(), "local".into()],
+        domain: AWDLDnsCompression::Null,
+    };
+    assert_eq!(name.to_string(), "simon.local");
+    assert_ne!(
+        name,
+        AWDLDnsName {
+            labels: ["simon".into(), "local".into()],
+            domain: AWDLDnsCompression::Local,
+        }
+    );
+    assert_eq!(
+        AWDLDnsName {
+            labels: ["simon".into()],
+            domain: AWDLDnsCompression::Unknown(0xc0ff),
+        }
+        .to_string(),
+        "simon.<0xc0ff>"
+    );
 }
@@ -1,18 +1,28 @@
-use core::ops::{Deref, DerefMut};
+use core::{
+    fmt::{Display, Write},
+    ops::{Deref, DerefMut},
+    str::from_utf8,
+};
 
 use scroll::{
-    ctx::{MeasureWith, StrCtx, TryFromCtx, TryIntoCtx},
+    ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
     Pread, Pwrite,
 };
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 /// A string in the format used by AWDL.
 /// The characters are preceeded by a length byte.
-pub struct AWDLStr<'a>(pub &'a str);
+///
+/// The bytes aren't required to be valid UTF-8, so that labels from all peers are kept byte exact.
+pub struct AWDLStr<'a>(pub &'a [u8]);
 impl<'a> AWDLStr<'a> {
     pub const fn size_in_bytes(&'a self) -> usize {
         self.0.len() + 1
     }
+    /// Returns the string, if it's valid UTF-8.
+    pub fn as_str(&self) -> Option<&'a str> {
+        from_utf8(self.0).ok()
+    }
 }
 impl<'a> MeasureWith<()> for AWDLStr<'a> {
     fn measure_with(&self, _ctx: &()) -> usize {
@@ -25,8 +35,8 @@ impl<'a> TryFromCtx<'a> for AWDLStr<'a> {
         let mut offset = 0;
 
         let length = from.gread::<u8>(&mut offset)? as usize;
-        let str = from.gread_with::<&'a str>(&mut offset, StrCtx::Length(length))?;
-        Ok((Self(str), offset))
+        let bytes = from.gread_with::<&'a [u8]>(&mut offset, length)?;
+        Ok((Self(bytes), offset))
     }
 }
 impl<'a> TryIntoCtx for AWDLStr<'a> {
@@ -45,7 +55,7 @@ impl<'a> TryIntoCtx for AWDLStr<'a> {
     }
 }
 impl<'a> Deref for AWDLStr<'a> {
-    type Target = &'a str;
+    type Target = &'a [u8];
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -57,9 +67,39 @@ impl<'a> DerefMut for AWDLStr<'a> {
 }
 impl<'a> From<&'a str> for AWDLStr<'a> {
     fn from(value: &'a str) -> Self {
+        Self(value.as_bytes())
+    }
+}
+impl<'a> From<&'a [u8]> for AWDLStr<'a> {
+    fn from(value: &'a [u8]) -> Self {
         Self(value)
     }
 }
+impl Display for AWDLStr<'_> {
+    /// Format the label like specified in RFC 6763.
+    ///
+    /// Dots and backslashes are escaped with a backslash, while control characters and bytes, which aren't valid UTF-8, are written as `\DDD`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for chunk in self.0.utf8_chunks() {
+            for character in chunk.valid().chars() {
+                match character {
+                    '.' | '\\' => {
+                        f.write_char('\\')?;
+                        f.write_char(character)?;
+                    }
+                    character if character.is_ascii_control() => {
+                        write!(f, "\\{:03}", character as u8)?
+                    }
+                    character => f.write_char(character)?,
+                }
+            }
+            for byte in chunk.invalid() {
+                write!(f, "\\{byte:03}")?;
+            }
+        }
+        Ok(())
+    }
+}
 #[cfg(test)]
 #[test]
 fn test_awdl_str() {
@@ -72,3 +112,18 @@ fn test_awdl_str() {
     let _ = buf.pwrite::<AWDLStr<'_>>(string, 0).unwrap();
     assert_eq!(bytes, buf);
 }
+#[cfg(test)]
+#[test]
+fn test_awdl_str_non_utf8() {
+    use alloc::string::ToString;
+
+    let bytes = [0x05, 0x4a, 0xfc, 0x72, 0x67, 0x2e].as_slice();
+    let string = bytes.pread::<AWDLStr<'_>>(0).unwrap();
+    assert_eq!(string.as_str(), None);
+    assert_eq!(string.to_string(), "J\\252rg\\.");
+    let mut buf = [0x00; 6];
+    buf.pwrite(string, 0).unwrap();
+    assert_eq!(bytes, buf);
+
+    assert_eq!(AWDLStr::from("Jürg\\\x7f").to_string(), "Jürg\\\\\\127");
+}
@@ -0,0 +1,309 @@
+use scroll::{Pread, Pwrite, NETWORK};
+
+use super::{AWDLDnsCompression, AWDLDnsName, AWDLStr};
+
+/// The maximum length of a name in DNS wire format, including the length bytes and the terminating zero.
+pub const MAX_WIRE_NAME_LENGTH: usize = 255;
+
+/// All [compressions](AWDLDnsCompression), which can be expanded to labels.
+const KNOWN_COMPRESSIONS: [AWDLDnsCompression; 15] = [
+    AWDLDnsCompression::Null,
+    AWDLDnsCompression::AirPlayTcpLocal,
+    AWDLDnsCompression::AirPlayUdpLocal,
+    AWDLDnsCompression::AirPlay,
+    AWDLDnsCompression::RaopTcpLocal,
+    AWDLDnsCompression::RaopUdpLocal,
+    AWDLDnsCompression::Raop,
+    AWDLDnsCompression::AirDropTcpLocal,
+    AWDLDnsCompression::AirDropUdpLocal,
+    AWDLDnsCompression::AirDrop,
+    AWDLDnsCompression::TcpLocal,
+    AWDLDnsCompression::UdpLocal,
+    AWDLDnsCompression::Local,
+    AWDLDnsCompression::Ip6Arpa,
+    AWDLDnsCompression::Ip4Arpa,
+];
+
+impl AWDLDnsCompression {
+    /// Returns an iterator over the labels, to which the compression expands.
+    ///
+    /// This returns [None] for [unknown](AWDLDnsCompression::Unknown) compressions.
+    pub fn labels(&self) -> Option<impl Iterator<Item = &'static str> + Clone> {
+        Some(
+            self.to_static_string()?
+                .split('.')
+                .filter(|label| !label.is_empty()),
+        )
+    }
+    /// Returns the compression, which covers the most trailing labels.
+    ///
+    /// Like all DNS names, the labels are compared case insensitively.
+    /// The number of labels not covered by the compression is returned alongside it.
+    pub fn best_suffix<'a>(labels: impl IntoIterator<Item = AWDLStr<'a>> + Clone) -> (usize, Self) {
+        let label_count = labels.clone().into_iter().count();
+        KNOWN_COMPRESSIONS
+            .iter()
+            .filter_map(|compression| {
+                let compression_labels = compression.labels()?;
+                let covered = compression_labels.clone().count();
+                (covered <= label_count
+                    && labels
+                        .clone()
+                        .into_iter()
+                        .skip(label_count - covered)
+                        .zip(compression_labels)
+                        .all(|(label, compression_label)| {
+                            label.0.eq_ignore_ascii_case(compression_label.as_bytes())
+                        }))
+                .then(|| (label_count - covered, *compression))
+            })
+            .min_by_key(|(remaining, _)| *remaining)
+            .unwrap_or((label_count, Self::Null))
+    }
+}
+
+/// Read the label at the position, following compression pointers.
+///
+/// The position is advanced past the label. If the end of the name is reached, [None] is returned.
+/// Pointers have to point before the pointer limit, which is lowered with every pointer followed, preventing loops.
+fn read_wire_label<'a>(
+    message: &'a [u8],
+    position: &mut usize,
+    pointer_limit: &mut usize,
+    follow_pointers: bool,
+) -> Result<Option<&'a [u8]>, scroll::Error> {
+    loop {
+        let length = message.pread::<u8>(*position)?;
+        match length {
+            0 if follow_pointers => return Ok(None),
+            0xc0..=0xff if follow_pointers => {
+                let pointer = (message.pread_with::<u16>(*position, NETWORK)? & 0x3fff) as usize;
+                if pointer >= *pointer_limit {
+                    return Err(scroll::Error::BadInput {
+                        size: *position,
+                        msg: "DNS compression pointer doesn't point backwards.",
+                    });
+                }
+                *pointer_limit = pointer;
+                *position = pointer;
+            }
+            0x40..=0xbf if follow_pointers => {
+                return Err(scroll::Error::BadInput {
+                    size: *position,
+                    msg: "Unsupported DNS label type.",
+                })
+            }
+            _ => {
+                let label = message.pread_with(*position + 1, length as usize)?;
+                *position += 1 + length as usize;
+                return Ok(Some(label));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// An iterator over labels in DNS wire format, which were read from a DNS message.
+///
+/// Compression pointers into the rest of the message are followed.
+/// All labels are validated, when the iterator is created by [AWDLDnsName::from_wire] or [WireLabelIterator::character_strings].
+pub struct WireLabelIterator<'a> {
+    message: &'a [u8],
+    position: usize,
+    pointer_limit: usize,
+    remaining: usize,
+    follow_pointers: bool,
+}
+impl<'a> WireLabelIterator<'a> {
+    /// Create an iterator over the character strings in the bytes, like the ones in a TXT record.
+    pub fn character_strings(bytes: &'a [u8]) -> Result<Self, scroll::Error> {
+        let mut position = 0;
+        let mut remaining = 0;
+        while position < bytes.len() {
+            read_wire_label(bytes, &mut position, &mut 0, false)?;
+            remaining += 1;
+        }
+        Ok(Self {
+            message: bytes,
+            position: 0,
+            pointer_limit: 0,
+            remaining,
+            follow_pointers: false,
+        })
+    }
+}
+impl<'a> Iterator for WireLabelIterator<'a> {
+    type Item = AWDLStr<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // The labels are validated, when the iterator is created, so reading them can't fail.
+        // Should it fail anyway, the iteration is ended instead of yielding a truncated label.
+        match read_wire_label(
+            self.message,
+            &mut self.position,
+            &mut self.pointer_limit,
+            self.follow_pointers,
+        ) {
+            Ok(label) => label.map(AWDLStr),
+            Err(_) => {
+                self.remaining = 0;
+                None
+            }
+        }
+    }
+}
+
+impl<'a> AWDLDnsName<WireLabelIterator<'a>> {
+    /// Read a name in DNS wire format at the offset in the message.
+    ///
+    /// The best matching [compression](AWDLDnsCompression) is chosen as the domain.
+    /// Returns the name and the number of bytes it occupies at the offset.
+    pub fn from_wire(message: &'a [u8], offset: usize) -> Result<(Self, usize), scroll::Error> {
+        let mut position = offset;
+        let mut pointer_limit = offset;
+        let mut length = None;
+        let mut label_count = 0;
+        let mut wire_length = 1;
+        loop {
+            if message.pread::<u8>(position)? >= 0xc0 {
+                length.get_or_insert(position + 2 - offset);
+            }
+            let Some(label) = read_wire_label(message, &mut position, &mut pointer_limit, true)?
+            else {
+                break;
+            };
+            label_count += 1;
+            wire_length += 1 + label.len();
+            if wire_length > MAX_WIRE_NAME_LENGTH {
+                return Err(scroll::Error::TooBig {
+                    size: MAX_WIRE_NAME_LENGTH,
+                    len: wire_length,
+                });
+            }
+        }
+        let labels = WireLabelIterator {
+            message,
+            position: offset,
+            pointer_limit: offset,
+            remaining: label_count,
+            follow_pointers: true,
+        };
+        let (remaining, domain) = AWDLDnsCompression::best_suffix(labels);
+        Ok((
+            Self {
+                labels: WireLabelIterator {
+                    remaining,
+                    ..labels
+                },
+                domain,
+            },
+            length.unwrap_or_else(|| position + 1 - offset),
+        ))
+    }
+}
+impl<'a, I> AWDLDnsName<I>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    /// Returns the length of the name in uncompressed DNS wire format.
+    ///
+    /// [Unknown](AWDLDnsCompression::Unknown) domains are treated as empty, since they can't be written.
+    pub fn wire_length(&self) -> usize {
+        self.labels
+            .clone()
+            .into_iter()
+            .map(|label| label.len() + 1)
+            .sum::<usize>()
+            + self
+                .domain
+                .labels()
+                .map(|labels| labels.map(|label| label.len() + 1).sum::<usize>())
+                .unwrap_or_default()
+            + 1
+    }
+    /// Write the name in uncompressed DNS wire format, with the domain expanded.
+    pub fn write_wire(&self, buf: &mut [u8]) -> Result<usize, scroll::Error> {
+        let Some(domain_labels) = self.domain.labels() else {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Unknown compressions can't be expanded.",
+            });
+        };
+        if self.wire_length() > MAX_WIRE_NAME_LENGTH {
+            return Err(scroll::Error::TooBig {
+                size: MAX_WIRE_NAME_LENGTH,
+                len: self.wire_length(),
+            });
+        }
+        let mut offset = 0;
+        for label in self
+            .labels
+            .clone()
+            .into_iter()
+            .map(|label| label.0)
+            .chain(domain_labels.map(|label| -> &'a [u8] { label.as_bytes() }))
+        {
+            if label.is_empty() || label.len() > 63 {
+                return Err(scroll::Error::BadInput {
+                    size: offset,
+                    msg: "DNS labels must be between 1 and 63 bytes long.",
+                });
+            }
+            buf.gwrite(AWDLStr(label), &mut offset)?;
+        }
+        buf.gwrite(0u8, &mut offset)?;
+        Ok(offset)
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_dns_wire_name() {
+    let name = AWDLDnsName {
+        labels: ["34fd6a0c9a42@1.021".into(), "_airplay-p2p".into()],
+        domain: AWDLDnsCompression::TcpLocal,
+    };
+    let mut buf = [0x00; 0x40];
+    let length = name.write_wire(&mut buf).unwrap();
+    assert_eq!(length, name.wire_length());
+    assert_eq!(
+        &buf[..length],
+        b"\x1234fd6a0c9a42@1.021\x0c_airplay-p2p\x04_tcp\x05local\x00"
+    );
+    let (read_name, read_length) = AWDLDnsName::from_wire(&buf, 0).unwrap();
+    assert_eq!(read_length, length);
+    assert_eq!(read_name, name);
+    assert_eq!(read_name.domain, AWDLDnsCompression::TcpLocal);
+
+    // The name of the service is reused through a compression pointer.
+    let message = b"\x0c_airplay-p2p\x04_tcp\x05local\x00\x04peer\xc0\x00";
+    let (read_name, read_length) = AWDLDnsName::from_wire(message, 25).unwrap();
+    assert_eq!(read_length, 7);
+    assert_eq!(
+        read_name,
+        AWDLDnsName {
+            labels: ["peer".into(), "_airplay-p2p".into()],
+            domain: AWDLDnsCompression::TcpLocal,
+        }
+    );
+
+    // DNS names are case insensitive.
+    let (read_name, _) = AWDLDnsName::from_wire(b"\x08_AirDrop\x04_tcp\x05Local\x00", 0).unwrap();
+    assert_eq!(read_name.domain, AWDLDnsCompression::AirDropTcpLocal);
+    assert_eq!(read_name.labels.count(), 0);
+
+    let (read_name, _) = AWDLDnsName::from_wire(b"\x04host\x03com\x00", 0).unwrap();
+    assert_eq!(read_name.domain, AWDLDnsCompression::Null);
+    assert_eq!(read_name.labels.count(), 2);
+
+    // A pointer to itself would loop forever.
+    assert!(AWDLDnsName::from_wire(b"\x04peer\xc0\x00", 0).is_err());
+    assert!(AWDLDnsName {
+        labels: ["peer".into()],
+        domain: AWDLDnsCompression::Unknown(0xc0ff),
+    }
+    .write_wire(&mut buf)
+    .is_err());
+}
@@ -2,8 +2,10 @@ mod awdl_dns_compression;
 mod awdl_dns_name;
 mod awdl_str;
 mod awdl_version;
+mod dns_wire;
 
 pub use awdl_dns_compression::AWDLDnsCompression;
 pub use awdl_dns_name::{AWDLDnsName, DefaultAWDLDnsName, ReadLabelIterator};
 pub use awdl_str::AWDLStr;
 pub use awdl_version::AWDLVersion;
+pub use dns_wire::{WireLabelIterator, MAX_WIRE_NAME_LENGTH};
@@ -4,6 +4,28 @@
 #[cfg(test)]
 extern crate alloc;
 
+/// Generates consuming setters for a builder, which wraps the TLV it builds in a field called `tlv`.
+macro_rules! builder_setters {
+    (optional $($(#[$meta:meta])* $field:ident: $field_type:ty),*) => {
+        $(
+            $(#[$meta])*
+            pub fn $field(mut self, $field: $field_type) -> Self {
+                self.tlv.$field = Some($field);
+                self
+            }
+        )*
+    };
+    ($($(#[$meta:meta])* $field:ident: $field_type:ty),*) => {
+        $(
+            $(#[$meta])*
+            pub fn $field(mut self, $field: $field_type) -> Self {
+                self.tlv.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
 /// Structures related to AWDL action frames.
 pub mod action_frame;
 /// Structures, which are shared between multiple TLVs and frame types.
@@ -0,0 +1,146 @@
+use core::fmt::Display;
+
+use super::DataPathStateTLV;
+use crate::tlvs::data_path::{HTCapabilitiesTLV, IEEE80211ContainerTLV};
+
+/// The element ID of the VHT capabilities in an [IEEE80211ContainerTLV].
+const VHT_CAPABILITIES_ELEMENT_ID: u8 = 191;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The capabilities of a peer, merged from the [DataPathStateTLV], the [HTCapabilitiesTLV] and the [IEEE80211ContainerTLV].
+pub struct DataPathCapabilities {
+    /// 802.11n (HT) is supported.
+    pub high_throughput: bool,
+    /// 802.11ac (VHT) is supported.
+    pub very_high_throughput: bool,
+    /// 802.11ax (HE) is supported.
+    pub high_efficiency: bool,
+    /// The 6GHz band is supported.
+    pub wifi_six_e: bool,
+    /// Real simultaneous dual band is supported.
+    pub rsdb: bool,
+    /// Both the 2.4GHz and 5GHz bands are supported.
+    pub dual_band: bool,
+    /// The peer can act as an AirPlay sink.
+    pub airplay_sink: bool,
+    /// AirPlay solo mode is supported.
+    pub airplay_solo_mode: bool,
+    /// UMI is supported.
+    pub umi: bool,
+    /// The peer is a sidekick hub.
+    pub sidekick_hub: bool,
+    /// The peer can be ranged.
+    pub rangeable: bool,
+    /// Ranging discovery is supported.
+    pub ranging_discovery: bool,
+    /// The peer can act as a DFS proxy.
+    pub dfs_proxy: bool,
+    /// Ultra low latency in the infrastructure network is supported.
+    pub ultra_low_latency_infra: bool,
+}
+impl DataPathCapabilities {
+    /// Returns an iterator over the names of all supported capabilities.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + Clone {
+        [
+            (self.high_throughput, "HT"),
+            (self.very_high_throughput, "VHT"),
+            (self.high_efficiency, "HE"),
+            (self.wifi_six_e, "Wi-Fi 6E"),
+            (self.rsdb, "RSDB"),
+            (self.dual_band, "Dual band"),
+            (self.airplay_sink, "AirPlay sink"),
+            (self.airplay_solo_mode, "AirPlay solo mode"),
+            (self.umi, "UMI"),
+            (self.sidekick_hub, "Sidekick hub"),
+            (self.rangeable, "Rangeable"),
+            (self.ranging_discovery, "Ranging discovery"),
+            (self.dfs_proxy, "DFS proxy"),
+            (self.ultra_low_latency_infra, "Ultra low latency infra"),
+        ]
+        .into_iter()
+        .filter_map(|(supported, name)| supported.then_some(name))
+    }
+}
+impl Display for DataPathCapabilities {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, name) in self.names().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+impl DataPathStateTLV<'_> {
+    /// Returns the capabilities of the peer, merged with the HT and VHT capabilities from the same frame.
+    ///
+    /// If the extended flags are absent, the capabilities signaled by them are assumed to be unsupported.
+    /// The unicast options aren't taken into account, since they are requests for the current frame and not capabilities.
+    /// The RLFC isn't taken into account either, since its layout is unknown.
+    pub fn capabilities(
+        &self,
+        ht_capabilities: Option<&HTCapabilitiesTLV>,
+        ieee80211_container: Option<&IEEE80211ContainerTLV>,
+    ) -> DataPathCapabilities {
+        let extended_flags = self.extended_flags.unwrap_or_default();
+        DataPathCapabilities {
+            high_throughput: ht_capabilities.is_some(),
+            very_high_throughput: ieee80211_container
+                .is_some_and(|container| container.tlv.tlv_type == VHT_CAPABILITIES_ELEMENT_ID),
+            high_efficiency: extended_flags.high_efficiency_support,
+            wifi_six_e: extended_flags.wifi_six_e_support,
+            rsdb: self.flags.rsdb_support,
+            dual_band: self.flags.dualband_support,
+            airplay_sink: self.flags.airplay_sink,
+            airplay_solo_mode: self.flags.airplay_solo_mode_support,
+            umi: self.flags.umi_support,
+            sidekick_hub: extended_flags.is_sidekick_hub,
+            rangeable: self.flags.rangeable,
+            ranging_discovery: extended_flags.ranging_discovery_supported,
+            dfs_proxy: extended_flags.dfs_proxy_support,
+            ultra_low_latency_infra: extended_flags.ultra_low_latency_infra_support,
+        }
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_data_path_capabilities() {
+    use alloc::string::ToString;
+    use scroll::Pread;
+
+    use crate::action_frame::DefaultAWDLActionFrame;
+
+    let bytes = include_bytes!("../../../../test_bins/data_path_state_tlv.bin");
+    let data_path_state = bytes.pread::<DataPathStateTLV>(0).unwrap();
+    let capabilities = data_path_state.capabilities(None, None);
+    assert_eq!(
+        capabilities,
+        DataPathCapabilities {
+            dual_band: true,
+            airplay_solo_mode: true,
+            umi: true,
+            dfs_proxy: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        capabilities.to_string(),
+        "Dual band, AirPlay solo mode, UMI, DFS proxy"
+    );
+    assert_eq!(DataPathCapabilities::default().to_string(), "");
+
+    let tlvs = include_bytes!("../../../../test_bins/mif.bin")
+        .pread::<DefaultAWDLActionFrame>(0)
+        .unwrap()
+        .tagged_data;
+    let capabilities = tlvs
+        .get_first_tlv::<DataPathStateTLV>()
+        .unwrap()
+        .capabilities(
+            tlvs.get_first_tlv::<HTCapabilitiesTLV>().as_ref(),
+            tlvs.get_first_tlv::<IEEE80211ContainerTLV>().as_ref(),
+        );
+    assert!(capabilities.high_throughput);
+    assert!(capabilities.very_high_throughput);
+}
@@ -0,0 +1,338 @@
+use core::fmt::{Display, Formatter};
+
+use macro_bits::serializable_enum;
+use scroll::{
+    ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
+    Pread, Pwrite,
+};
+
+use crate::tlvs::sync_elect::{
+    channel::{Band, Channel},
+    channel_sequence::ChannelSequence,
+};
+
+/// All officially assigned ISO 3166-1 alpha-2 codes.
+const ISO_3166_ALPHA_2_CODES: &[[u8; 2]] = &[
+    *b"AD", *b"AE", *b"AF", *b"AG", *b"AI", *b"AL", *b"AM", *b"AO", *b"AQ", *b"AR", *b"AS", *b"AT",
+    *b"AU", *b"AW", *b"AX", *b"AZ", *b"BA", *b"BB", *b"BD", *b"BE", *b"BF", *b"BG", *b"BH", *b"BI",
+    *b"BJ", *b"BL", *b"BM", *b"BN", *b"BO", *b"BQ", *b"BR", *b"BS", *b"BT", *b"BV", *b"BW", *b"BY",
+    *b"BZ", *b"CA", *b"CC", *b"CD", *b"CF", *b"CG", *b"CH", *b"CI", *b"CK", *b"CL", *b"CM", *b"CN",
+    *b"CO", *b"CR", *b"CU", *b"CV", *b"CW", *b"CX", *b"CY", *b"CZ", *b"DE", *b"DJ", *b"DK", *b"DM",
+    *b"DO", *b"DZ", *b"EC", *b"EE", *b"EG", *b"EH", *b"ER", *b"ES", *b"ET", *b"FI", *b"FJ", *b"FK",
+    *b"FM", *b"FO", *b"FR", *b"GA", *b"GB", *b"GD", *b"GE", *b"GF", *b"GG", *b"GH", *b"GI", *b"GL",
+    *b"GM", *b"GN", *b"GP", *b"GQ", *b"GR", *b"GS", *b"GT", *b"GU", *b"GW", *b"GY", *b"HK", *b"HM",
+    *b"HN", *b"HR", *b"HT", *b"HU", *b"ID", *b"IE", *b"IL", *b"IM", *b"IN", *b"IO", *b"IQ", *b"IR",
+    *b"IS", *b"IT", *b"JE", *b"JM", *b"JO", *b"JP", *b"KE", *b"KG", *b"KH", *b"KI", *b"KM", *b"KN",
+    *b"KP", *b"KR", *b"KW", *b"KY", *b"KZ", *b"LA", *b"LB", *b"LC", *b"LI", *b"LK", *b"LR", *b"LS",
+    *b"LT", *b"LU", *b"LV", *b"LY", *b"MA", *b"MC", *b"MD", *b"ME", *b"MF", *b"MG", *b"MH", *b"MK",
+    *b"ML", *b"MM", *b"MN", *b"MO", *b"MP", *b"MQ", *b"MR", *b"MS", *b"MT", *b"MU", *b"MV", *b"MW",
+    *b"MX", *b"MY", *b"MZ", *b"NA", *b"NC", *b"NE", *b"NF", *b"NG", *b"NI", *b"NL", *b"NO", *b"NP",
+    *b"NR", *b"NU", *b"NZ", *b"OM", *b"PA", *b"PE", *b"PF", *b"PG", *b"PH", *b"PK", *b"PL", *b"PM",
+    *b"PN", *b"PR", *b"PS", *b"PT", *b"PW", *b"PY", *b"QA", *b"RE", *b"RO", *b"RS", *b"RU", *b"RW",
+    *b"SA", *b"SB", *b"SC", *b"SD", *b"SE", *b"SG", *b"SH", *b"SI", *b"SJ", *b"SK", *b"SL", *b"SM",
+    *b"SN", *b"SO", *b"SR", *b"SS", *b"ST", *b"SV", *b"SX", *b"SY", *b"SZ", *b"TC", *b"TD", *b"TF",
+    *b"TG", *b"TH", *b"TJ", *b"TK", *b"TL", *b"TM", *b"TN", *b"TO", *b"TR", *b"TT", *b"TV", *b"TW",
+    *b"TZ", *b"UA", *b"UG", *b"UM", *b"US", *b"UY", *b"UZ", *b"VA", *b"VC", *b"VE", *b"VG", *b"VI",
+    *b"VN", *b"VU", *b"WF", *b"WS", *b"YE", *b"YT", *b"ZA", *b"ZM", *b"ZW",
+];
+
+serializable_enum! {
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    /// The environment, in which the device is operating.
+    ///
+    /// This is the third octet of the 802.11 country string.
+    pub enum CountryEnvironment : u8 {
+        #[default]
+        /// No environment was specified.
+        ///
+        /// This is sent by macOS, although it isn't defined by 802.11.
+        Unspecified => 0x00,
+        /// Indoor and outdoor.
+        Any => b' ',
+        /// Outdoor only.
+        Outdoor => b'O',
+        /// Indoor only.
+        Indoor => b'I',
+        /// A non-country entity.
+        NonCountry => b'X'
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The country code of a peer.
+///
+/// The code is kept as it was received, so [is_valid](Self::is_valid) should be checked, before trusting it.
+pub struct CountryCode {
+    /// The ISO 3166-1 alpha-2 code.
+    pub code: [u8; 2],
+    pub environment: CountryEnvironment,
+}
+impl CountryCode {
+    /// Create a new country code.
+    ///
+    /// This returns [None], if the code isn't an assigned ISO 3166-1 alpha-2 code.
+    pub fn new(code: &str, environment: CountryEnvironment) -> Option<Self> {
+        let country_code = Self {
+            code: code.as_bytes().try_into().ok()?,
+            environment,
+        };
+        country_code.is_valid().then_some(country_code)
+    }
+    /// Check if the code is an assigned ISO 3166-1 alpha-2 code.
+    pub fn is_valid(&self) -> bool {
+        ISO_3166_ALPHA_2_CODES.contains(&self.code)
+    }
+    /// Returns the code as a string, if it's valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.code).ok()
+    }
+    /// Returns the [regulatory domain](RegulatoryDomain) of the country.
+    ///
+    /// This is [None], if the country isn't covered by the built-in table.
+    pub fn regulatory_domain(&self) -> Option<RegulatoryDomain> {
+        RegulatoryDomain::from_country_code(self.code)
+    }
+    /// Check if the channel may be used in the country.
+    ///
+    /// This is [None], if the country isn't covered by the built-in table.
+    pub fn is_channel_allowed(&self, channel: &Channel) -> Option<bool> {
+        self.regulatory_domain()
+            .map(|regulatory_domain| regulatory_domain.is_channel_allowed(channel))
+    }
+    /// Check if all channels of the sequence may be used in the country.
+    ///
+    /// This is [None], if the country isn't covered by the built-in table.
+    pub fn is_channel_sequence_allowed<const N: usize>(
+        &self,
+        channel_sequence: &ChannelSequence<N>,
+    ) -> Option<bool> {
+        self.regulatory_domain().map(|regulatory_domain| {
+            regulatory_domain
+                .illegal_channels(channel_sequence)
+                .next()
+                .is_none()
+        })
+    }
+}
+impl Display for CountryCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str().unwrap_or("??"))
+    }
+}
+impl MeasureWith<()> for CountryCode {
+    fn measure_with(&self, _ctx: &()) -> usize {
+        3
+    }
+}
+impl<'a> TryFromCtx<'a> for CountryCode {
+    type Error = scroll::Error;
+    fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+        Ok((
+            Self {
+                code: from.gread(&mut offset)?,
+                environment: CountryEnvironment::from_bits(from.gread(&mut offset)?),
+            },
+            offset,
+        ))
+    }
+}
+impl TryIntoCtx for CountryCode {
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+        buf.gwrite(self.code, &mut offset)?;
+        buf.gwrite(self.environment.into_bits(), &mut offset)?;
+        Ok(offset)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A simplified regulatory domain, describing which 20MHz channels may be used.
+///
+/// This only covers the channels usable by AWDL and ignores power limits and DFS requirements.
+pub enum RegulatoryDomain {
+    /// The United States and its territories, as well as Canada.
+    Fcc,
+    /// The member states of the EU and EFTA, as well as the UK.
+    Etsi,
+    /// Japan.
+    Japan,
+    /// China.
+    China,
+}
+impl RegulatoryDomain {
+    /// Returns the regulatory domain of the country.
+    pub fn from_country_code(code: [u8; 2]) -> Option<Self> {
+        Some(match &code {
+            b"US" | b"CA" | b"AS" | b"GU" | b"MP" | b"PR" | b"UM" | b"VI" => Self::Fcc,
+            b"AT" | b"BE" | b"BG" | b"CH" | b"CY" | b"CZ" | b"DE" | b"DK" | b"EE" | b"ES"
+            | b"FI" | b"FR" | b"GB" | b"GR" | b"HR" | b"HU" | b"IE" | b"IS" | b"IT" | b"LI"
+            | b"LT" | b"LU" | b"LV" | b"MT" | b"NL" | b"NO" | b"PL" | b"PT" | b"RO" | b"SE"
+            | b"SI" | b"SK" => Self::Etsi,
+            b"JP" => Self::Japan,
+            b"CN" => Self::China,
+            _ => return None,
+        })
+    }
+    /// Returns the inclusive ranges of the center frequencies of the allowed 20MHz channels in MHz.
+    const fn allowed_frequencies(&self, band: Band) -> &'static [(u16, u16)] {
+        match (self, band) {
+            (Self::Fcc, Band::TwoPointFourGHz) => &[(2412, 2462)],
+            (Self::Etsi | Self::China, Band::TwoPointFourGHz) => &[(2412, 2472)],
+            (Self::Japan, Band::TwoPointFourGHz) => &[(2412, 2472), (2484, 2484)],
+            (Self::Fcc, Band::FiveGHz) => &[(5180, 5320), (5500, 5720), (5745, 5825)],
+            (Self::Etsi, Band::FiveGHz) => &[(5180, 5320), (5500, 5700)],
+            (Self::Japan, Band::FiveGHz) => &[(5180, 5320), (5500, 5720)],
+            (Self::China, Band::FiveGHz) => &[(5180, 5320), (5745, 5825)],
+            (Self::Fcc, Band::SixGHz) => &[(5935, 7115)],
+            (Self::Etsi | Self::Japan, Band::SixGHz) => &[(5935, 6415)],
+            _ => &[],
+        }
+    }
+    /// Check if the channel may be used.
+    ///
+    /// For channels wider than 20MHz, all 20MHz subchannels have to be allowed.
+    /// Channels, for which the frequency can't be determined, are never allowed.
+    pub fn is_channel_allowed(&self, channel: &Channel) -> bool {
+        let (Some(band), Some(center_frequency), Some(bandwidth)) = (
+            channel.band(),
+            channel.center_frequency(),
+            channel.bandwidth().and_then(|bandwidth| bandwidth.mhz()),
+        ) else {
+            return false;
+        };
+        let allowed_frequencies = self.allowed_frequencies(band);
+        let lowest_frequency = center_frequency - bandwidth / 2 + 10;
+        (0..bandwidth / 20)
+            .map(|subchannel| lowest_frequency + subchannel * 20)
+            .all(|frequency| {
+                allowed_frequencies
+                    .iter()
+                    .any(|(low, high)| (*low..=*high).contains(&frequency))
+            })
+    }
+    /// Returns an iterator over all channels of the sequence, which may not be used.
+    ///
+    /// Channels with the same primary channel are only reported once, if they also have the same bandwidth.
+    pub fn illegal_channels<'a, const N: usize>(
+        &'a self,
+        channel_sequence: &'a ChannelSequence<N>,
+    ) -> impl Iterator<Item = Channel> + 'a {
+        channel_sequence
+            .iter()
+            .enumerate()
+            .filter(|(index, channel)| {
+                channel_sequence.iter().position(|other| {
+                    other.is_same_channel(channel) && other.bandwidth() == channel.bandwidth()
+                }) == Some(*index)
+            })
+            .map(|(_, channel)| channel)
+            .filter(|channel| !self.is_channel_allowed(channel))
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_country_code() {
+    use crate::tlvs::sync_elect::channel::{
+        ChannelBandwidth, ChannelEncoding, LegacyFlags, SupportChannel,
+    };
+
+    let bytes = [b'D', b'E', 0x00];
+    let country_code = bytes.pread::<CountryCode>(0).unwrap();
+    assert_eq!(
+        country_code,
+        CountryCode::new("DE", CountryEnvironment::Unspecified).unwrap()
+    );
+    let mut buf = [0x00; 3];
+    buf.pwrite(country_code, 0).unwrap();
+    assert_eq!(buf, bytes);
+
+    let country_code = [b'U', b'S', b'I'].pread::<CountryCode>(0).unwrap();
+    assert_eq!(country_code.environment, CountryEnvironment::Indoor);
+    assert!(!CountryCode {
+        code: *b"XX",
+        ..Default::default()
+    }
+    .is_valid());
+    assert!(CountryCode::new("de", CountryEnvironment::Any).is_none());
+    assert!(CountryCode::new("DEU", CountryEnvironment::Any).is_none());
+
+    let germany = CountryCode::new("DE", CountryEnvironment::Any).unwrap();
+    let united_states = CountryCode::new("US", CountryEnvironment::Any).unwrap();
+    assert_eq!(germany.regulatory_domain(), Some(RegulatoryDomain::Etsi));
+    assert_eq!(
+        CountryCode::new("AQ", CountryEnvironment::Any)
+            .unwrap()
+            .regulatory_domain(),
+        None
+    );
+
+    let channel_13 = Channel::Simple { channel: 13 };
+    assert_eq!(germany.is_channel_allowed(&channel_13), Some(true));
+    assert_eq!(united_states.is_channel_allowed(&channel_13), Some(false));
+
+    let channel_149 = Channel::OpClass {
+        channel: 149,
+        opclass: 0x80,
+    };
+    assert_eq!(germany.is_channel_allowed(&channel_149), Some(false));
+    assert_eq!(united_states.is_channel_allowed(&channel_149), Some(true));
+
+    // 80MHz on 132 to 144 is only allowed, if channel 144 is.
+    let channel_140 = Channel::OpClass {
+        channel: 140,
+        opclass: 0x80,
+    };
+    assert_eq!(germany.is_channel_allowed(&channel_140), Some(false));
+    assert_eq!(united_states.is_channel_allowed(&channel_140), Some(true));
+
+    let channel_sequence = ChannelSequence::<16>::from_channels(
+        ChannelEncoding::Legacy,
+        [
+            Channel::Legacy {
+                flags: LegacyFlags {
+                    support_channel: SupportChannel::Lower,
+                    channel_bandwidth: ChannelBandwidth::EightyMHz,
+                    band: Band::FiveGHz,
+                },
+                channel: 46,
+            },
+            Channel::Simple { channel: 6 },
+            Channel::Simple { channel: 149 },
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        united_states.is_channel_sequence_allowed(&channel_sequence),
+        Some(true)
+    );
+    assert_eq!(
+        germany.is_channel_sequence_allowed(&channel_sequence),
+        Some(false)
+    );
+    assert!(RegulatoryDomain::Etsi
+        .illegal_channels(&channel_sequence)
+        .eq([Channel::Legacy {
+            flags: LegacyFlags {
+                support_channel: SupportChannel::Primary,
+                channel_bandwidth: ChannelBandwidth::TwentyMHz,
+                band: Band::FiveGHz,
+            },
+            channel: 149,
+        }]));
+
+    // The 80MHz channel mustn't be hidden by the 20MHz channel with the same primary channel.
+    let channel_sequence = ChannelSequence::<16>::from_channels(
+        ChannelEncoding::OpClass,
+        [Channel::Simple { channel: 140 }, channel_140],
+    )
+    .unwrap();
+    assert!(RegulatoryDomain::Etsi
+        .illegal_channels(&channel_sequence)
+        .eq([channel_140]));
+    assert_eq!(
+        germany.is_channel_sequence_allowed(&channel_sequence),
+        Some(false)
+    );
+}
@@ -1,9 +1,11 @@
-use macro_bits::{bit, bitfield, check_bit};
+use macro_bits::{bit, bitfield};
 use scroll::{
-    ctx::{TryFromCtx, TryIntoCtx},
+    ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
     Endian, Pread, Pwrite,
 };
 
+use crate::tlvs::sync_elect::channel::Channel;
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct DataPathStats {
     pub msec_since_activation: u32,
@@ -36,33 +38,75 @@ impl TryIntoCtx for DataPathStats {
 }
 bitfield! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    /// A map of the social channels, on which the peer is available.
     pub struct ChannelMap: u16 {
         pub channel_6: bool => bit!(0),
         pub channel_44: bool => bit!(1),
-        pub channel_149: bool => bit!(2)
+        pub channel_149: bool => bit!(2),
+        /// The remaining bits, which are preserved for round trips.
+        pub unknown: u16 => 0xfff8
+    }
+}
+impl ChannelMap {
+    /// Returns an iterator over the social channels selected by the map.
+    pub fn channels(&self) -> impl Iterator<Item = Channel> + Clone {
+        [
+            (self.channel_6, 6),
+            (self.channel_44, 44),
+            (self.channel_149, 149),
+        ]
+        .into_iter()
+        .filter_map(|(selected, channel)| selected.then_some(Channel::Simple { channel }))
     }
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The channel information of the peer.
+///
+/// Which variant is used, is determined by [DataPathExtendedFlags::is_social_channel_map_supported](super::DataPathExtendedFlags::is_social_channel_map_supported).
 pub enum DataPathChannel {
-    SingleChannel { channel: u8 },
+    /// A single channel, which is used by peers not supporting the social channel map.
+    SingleChannel {
+        channel: u8,
+        /// The upper byte, which is preserved for round trips.
+        unknown: u8,
+    },
+    /// A map of the social channels.
     ChannelMap(ChannelMap),
 }
 impl DataPathChannel {
-    pub fn from_u16(value: u16) -> Self {
-        if !check_bit!(value, bit!(0)) {
+    /// Interpret the value according to the support for the social channel map.
+    pub fn from_u16(value: u16, is_social_channel_map_supported: bool) -> Self {
+        if is_social_channel_map_supported {
+            Self::ChannelMap(ChannelMap::from_bits(value))
+        } else {
             Self::SingleChannel {
                 channel: value as u8,
+                unknown: (value >> 8) as u8,
             }
-        } else {
-            Self::ChannelMap(ChannelMap::from_bits(value))
         }
     }
     pub fn as_u16(&self) -> u16 {
         match *self {
-            DataPathChannel::SingleChannel { channel } => channel as u16,
+            DataPathChannel::SingleChannel { channel, unknown } => {
+                (unknown as u16) << 8 | channel as u16
+            }
             DataPathChannel::ChannelMap(channel_map) => channel_map.into_bits(),
         }
     }
+    /// Check if this is a [social channel map](ChannelMap).
+    pub const fn is_social_channel_map(&self) -> bool {
+        matches!(self, Self::ChannelMap(_))
+    }
+    /// Returns an iterator over the channels, on which the peer is available.
+    pub fn channels(&self) -> impl Iterator<Item = Channel> + Clone {
+        let (single_channel, channel_map) = match *self {
+            Self::SingleChannel { channel, .. } => {
+                (Some(Channel::Simple { channel }), ChannelMap::default())
+            }
+            Self::ChannelMap(channel_map) => (None, channel_map),
+        };
+        single_channel.into_iter().chain(channel_map.channels())
+    }
 }
 bitfield! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -94,3 +138,148 @@ bitfield! {
         pub stop_remote_camera: bool => bit!(31)
     }
 }
+bitfield! {
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    /// The extended unicast options.
+    ///
+    /// None of these bits have been observed being set yet, so their meaning is unknown.
+    pub struct UnicastOptionsExt: u32 {
+        /// The raw bits, which are preserved for round trips.
+        pub unknown: u32 => 0xffff_ffffu32
+    }
+}
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The unicast options of the peer.
+///
+/// On the wire, these are prefixed with their length, which is derived from the fields.
+pub struct DataPathUnicastOptions<'a> {
+    pub options: UnicastOptions,
+    /// The extended options and any bytes following them.
+    ///
+    /// The additional bytes haven't been observed yet, but are preserved for round trips.
+    pub extended: Option<(UnicastOptionsExt, &'a [u8])>,
+}
+impl DataPathUnicastOptions<'_> {
+    /// Returns the length of the options, excluding the length field.
+    pub const fn length(&self) -> usize {
+        match self.extended {
+            Some((_, additional_bytes)) => 8 + additional_bytes.len(),
+            None => 4,
+        }
+    }
+}
+impl From<UnicastOptions> for DataPathUnicastOptions<'_> {
+    fn from(options: UnicastOptions) -> Self {
+        Self {
+            options,
+            extended: None,
+        }
+    }
+}
+impl MeasureWith<()> for DataPathUnicastOptions<'_> {
+    fn measure_with(&self, _ctx: &()) -> usize {
+        2 + self.length()
+    }
+}
+impl<'a> TryFromCtx<'a> for DataPathUnicastOptions<'a> {
+    type Error = scroll::Error;
+    fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+        let length = from.gread_with::<u16>(&mut offset, Endian::Little)? as usize;
+        if length != 4 && length < 8 {
+            return Err(scroll::Error::BadInput {
+                size: offset,
+                msg: "Invalid unicast options length.",
+            });
+        }
+        let options = UnicastOptions::from_bits(from.gread_with(&mut offset, Endian::Little)?);
+        let extended = if length >= 8 {
+            Some((
+                UnicastOptionsExt::from_bits(from.gread_with(&mut offset, Endian::Little)?),
+                from.gread_with::<&[u8]>(&mut offset, length - 8)?,
+            ))
+        } else {
+            None
+        };
+        Ok((Self { options, extended }, offset))
+    }
+}
+impl TryIntoCtx for DataPathUnicastOptions<'_> {
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+        let length = u16::try_from(self.length()).map_err(|_| scroll::Error::TooBig {
+            size: u16::MAX as usize,
+            len: self.length(),
+        })?;
+        buf.gwrite_with(length, &mut offset, Endian::Little)?;
+        buf.gwrite_with(self.options.into_bits(), &mut offset, Endian::Little)?;
+        if let Some((options_ext, additional_bytes)) = self.extended {
+            buf.gwrite_with(options_ext.into_bits(), &mut offset, Endian::Little)?;
+            buf.gwrite(additional_bytes, &mut offset)?;
+        }
+        Ok(offset)
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_data_path_channel() {
+    for value in 0..=u16::MAX {
+        for is_social_channel_map_supported in [false, true] {
+            let data_path_channel =
+                DataPathChannel::from_u16(value, is_social_channel_map_supported);
+            assert_eq!(data_path_channel.as_u16(), value);
+            assert_eq!(
+                data_path_channel.is_social_channel_map(),
+                is_social_channel_map_supported
+            );
+        }
+    }
+    assert!(DataPathChannel::from_u16(0x0003, true).channels().eq([
+        Channel::Simple { channel: 6 },
+        Channel::Simple { channel: 44 }
+    ]));
+    assert!(DataPathChannel::from_u16(0xff05, true).channels().eq([
+        Channel::Simple { channel: 6 },
+        Channel::Simple { channel: 149 }
+    ]));
+    assert!(DataPathChannel::from_u16(0x0095, false)
+        .channels()
+        .eq([Channel::Simple { channel: 149 }]));
+}
+#[cfg(test)]
+#[test]
+fn test_data_path_unicast_options() {
+    use scroll::ctx::MeasureWith;
+
+    for (bytes, unicast_options) in [
+        (
+            &[0x04, 0x00, 0x02, 0x00, 0x00, 0x00][..],
+            DataPathUnicastOptions::from(UnicastOptions {
+                start_airplay: true,
+                ..Default::default()
+            }),
+        ),
+        (
+            &[
+                0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x80, 0xaa, 0xbb,
+            ][..],
+            DataPathUnicastOptions {
+                options: UnicastOptions::default(),
+                extended: Some((UnicastOptionsExt::from_bits(0x8000_0001), &[0xaa, 0xbb])),
+            },
+        ),
+    ] {
+        assert_eq!(
+            bytes.pread::<DataPathUnicastOptions>(0).unwrap(),
+            unicast_options
+        );
+        let mut buf = [0x00; 0x10];
+        let length = buf.pwrite(unicast_options, 0).unwrap();
+        assert_eq!(length, unicast_options.measure_with(&()));
+        assert_eq!(&buf[..length], bytes);
+    }
+    assert!([0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        .pread::<DataPathUnicastOptions>(0)
+        .is_err());
+}
@@ -1,4 +1,7 @@
+mod capabilities;
+mod country_code;
 mod misc;
+mod session_tracker;
 
 use mac_parser::MACAddress;
 use macro_bits::{bit, bitfield};
@@ -7,7 +10,17 @@ use scroll::{
     Endian, Pread, Pwrite,
 };
 
-pub use self::misc::{DataPathStats, DataPathChannel, UnicastOptions, ChannelMap};
+use crate::tlvs::{AWDLTLVType, AwdlTlv};
+
+pub use self::{
+    capabilities::DataPathCapabilities,
+    country_code::{CountryCode, CountryEnvironment, RegulatoryDomain},
+    misc::{
+        ChannelMap, DataPathChannel, DataPathStats, DataPathUnicastOptions, UnicastOptions,
+        UnicastOptionsExt,
+    },
+    session_tracker::{UnicastEvent, UnicastEventKind, UnicastSession, UnicastSessionTracker},
+};
 
 bitfield! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -40,6 +53,8 @@ bitfield! {
         pub dynamic_sdb_active: bool => bit!(4),
         pub stats_present: bool => bit!(5),
         pub dfs_proxy_support: bool => bit!(6),
+        /// This bit hasn't been observed being set yet.
+        pub reserved: bool => bit!(7),
         pub high_efficiency_support: bool => bit!(8),
         pub is_sidekick_hub: bool => bit!(9),
         pub fast_discovery_active: bool => bit!(10),
@@ -51,22 +66,29 @@ bitfield! {
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub struct DataPathStateTLV {
+pub struct DataPathStateTLV<'a> {
     pub flags: DataPathFlags,
-    pub country_code: Option<[char; 2]>,
+    pub country_code: Option<CountryCode>,
     pub channel_map: Option<DataPathChannel>,
     pub infra_bssid_channel: Option<(MACAddress, u16)>,
     pub infra_address: Option<MACAddress>,
     pub awdl_address: Option<MACAddress>,
-    pub unicast_options: Option<UnicastOptions>,
-    pub unicast_options_ext: Option<u32>,
+    pub unicast_options: Option<DataPathUnicastOptions<'a>>,
 
     pub extended_flags: Option<DataPathExtendedFlags>,
+    /// The layout of the RLFC is unknown, so it's kept as is.
     pub rlfc: Option<u32>,
     pub log_trigger_id: Option<u16>,
     pub stats: Option<DataPathStats>,
+    /// Any bytes following the known fields.
+    ///
+    /// These are appended by newer versions of macOS and preserved for round trips.
+    pub trailing: &'a [u8],
 }
-impl DataPathStateTLV {
+impl AwdlTlv for DataPathStateTLV<'_> {
+    const TLV_TYPE: AWDLTLVType = AWDLTLVType::DataPathState;
+}
+impl DataPathStateTLV<'_> {
     pub const fn size_in_bytes(&self) -> usize {
         let mut size = 2;
         if self.country_code.is_some() {
@@ -84,11 +106,8 @@ impl DataPathStateTLV {
         if self.awdl_address.is_some() {
             size += 6;
         }
-        if self.unicast_options.is_some() {
-            size += 6;
-        }
-        if self.unicast_options_ext.is_some() {
-            size += 4;
+        if let Some(unicast_options) = self.unicast_options {
+            size += 2 + unicast_options.length();
         }
         if self.extended_flags.is_some() {
             size += 2;
@@ -102,15 +121,15 @@ impl DataPathStateTLV {
         if self.stats.is_some() {
             size += 12;
         }
-        size
+        size + self.trailing.len()
     }
 }
-impl MeasureWith<()> for DataPathStateTLV {
+impl MeasureWith<()> for DataPathStateTLV<'_> {
     fn measure_with(&self, _ctx: &()) -> usize {
         self.size_in_bytes()
     }
 }
-impl<'a> TryFromCtx<'a> for DataPathStateTLV {
+impl<'a> TryFromCtx<'a> for DataPathStateTLV<'a> {
     type Error = scroll::Error;
     fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
         let mut offset = 0;
@@ -118,19 +137,12 @@ impl<'a> TryFromCtx<'a> for DataPathStateTLV {
             DataPathFlags::from_bits(from.gread_with(&mut offset, Endian::Little)?);
         let country_code = flags
             .country_code_present
-            .then(|| {
-                let country_code = from.gread::<[u8; 2]>(&mut offset)?.map(|x| x as char);
-                offset += 1;
-                Ok::<[char; 2], scroll::Error>(country_code)
-            })
+            .then(|| from.gread(&mut offset))
             .transpose()?;
+        // How the channel map is interpreted, depends on the extended flags, which come later.
         let channel_map = flags
             .channel_map_present
-            .then(|| {
-                Ok::<DataPathChannel, scroll::Error>(DataPathChannel::from_u16(
-                    from.gread_with(&mut offset, Endian::Little)?,
-                ))
-            })
+            .then(|| from.gread_with::<u16>(&mut offset, Endian::Little))
             .transpose()?;
         let infra_bssid_channel = flags
             .infra_bssid_channel_present
@@ -149,36 +161,10 @@ impl<'a> TryFromCtx<'a> for DataPathStateTLV {
             .awdl_address_present
             .then(|| from.gread(&mut offset))
             .transpose()?;
-        let (unicast_options, unicast_options_ext) = flags
+        let unicast_options = flags
             .unicast_options_present
-            .then(|| {
-                Ok({
-                    let unicast_options_length =
-                        from.gread_with::<u16>(&mut offset, Endian::Little)?;
-                    match unicast_options_length {
-                        4 => (
-                            Some(UnicastOptions::from_bits(
-                                from.gread_with(&mut offset, Endian::Little)?,
-                            )),
-                            None,
-                        ),
-                        8 => (
-                            Some(UnicastOptions::from_bits(
-                                from.gread_with(&mut offset, Endian::Little)?,
-                            )),
-                            Some(from.gread_with(&mut offset, Endian::Little)?),
-                        ),
-                        _ => {
-                            return Err(scroll::Error::BadInput {
-                                size: offset,
-                                msg: "Invalid unicast options length.",
-                            })
-                        }
-                    }
-                })
-            })
-            .transpose()?
-            .unwrap_or_default();
+            .then(|| from.gread(&mut offset))
+            .transpose()?;
         // I know, that I'm going to hell for this abomination.
         let (extended_flags, log_trigger_id, rlfc, stats) = flags
             .extended_flags
@@ -212,6 +198,15 @@ impl<'a> TryFromCtx<'a> for DataPathStateTLV {
             })
             .transpose()?
             .unwrap_or_default();
+        let trailing = &from[offset..];
+        offset = from.len();
+        let channel_map = channel_map.map(|channel_map| {
+            DataPathChannel::from_u16(
+                channel_map,
+                extended_flags
+                    .is_some_and(|extended_flags| extended_flags.is_social_channel_map_supported),
+            )
+        });
         Ok((
             DataPathStateTLV {
                 flags,
@@ -221,17 +216,17 @@ impl<'a> TryFromCtx<'a> for DataPathStateTLV {
                 infra_address,
                 awdl_address,
                 unicast_options,
-                unicast_options_ext,
                 extended_flags,
                 log_trigger_id,
                 rlfc,
                 stats,
+                trailing,
             },
             offset,
         ))
     }
 }
-impl TryIntoCtx for DataPathStateTLV {
+impl TryIntoCtx for DataPathStateTLV<'_> {
     type Error = scroll::Error;
     fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
         let mut offset = 0;
@@ -239,7 +234,14 @@ impl TryIntoCtx for DataPathStateTLV {
         let log_trigger_id_present = self.log_trigger_id.is_some();
         let rlfc_present = self.rlfc.is_some();
         let stats_present = self.stats.is_some();
-        let extended_flags = if log_trigger_id_present || rlfc_present || stats_present {
+        let social_channel_map_present = self
+            .channel_map
+            .is_some_and(|channel_map| channel_map.is_social_channel_map());
+        let extended_flags = if log_trigger_id_present
+            || rlfc_present
+            || stats_present
+            || social_channel_map_present
+        {
             let mut extended_flags = self.extended_flags.unwrap_or_default();
 
             extended_flags.log_trigger_id_present = log_trigger_id_present;
@@ -248,7 +250,15 @@ impl TryIntoCtx for DataPathStateTLV {
             Some(extended_flags)
         } else {
             self.extended_flags
-        };
+        }
+        .map(|mut extended_flags| {
+            // Otherwise the channel map would be interpreted differently, when reading it back.
+            if let Some(channel_map) = self.channel_map {
+                extended_flags.is_social_channel_map_supported =
+                    channel_map.is_social_channel_map();
+            }
+            extended_flags
+        });
 
         buf.gwrite_with(
             {
@@ -269,8 +279,7 @@ impl TryIntoCtx for DataPathStateTLV {
         )?;
 
         if let Some(country_code) = self.country_code {
-            buf.gwrite(country_code.map(|x| x as u8), &mut offset)?;
-            buf.gwrite(0u8, &mut offset)?;
+            buf.gwrite(country_code, &mut offset)?;
         }
         if let Some(channel_map) = self.channel_map {
             buf.gwrite_with(channel_map.as_u16(), &mut offset, Endian::Little)?;
@@ -285,30 +294,8 @@ impl TryIntoCtx for DataPathStateTLV {
         if let Some(awdl_address) = self.awdl_address {
             buf.gwrite(awdl_address, &mut offset)?;
         }
-        match (self.unicast_options, self.unicast_options_ext) {
-            (Some(unicast_options), None) => {
-                buf.gwrite(4u16, &mut offset)?;
-                buf.gwrite_with(
-                    unicast_options.into_bits(),
-                    &mut offset,
-                    Endian::Little,
-                )?;
-            }
-            (Some(unicast_options), Some(unicast_options_ext)) => {
-                buf.gwrite(8u16, &mut offset)?;
-                buf.gwrite_with(
-                    unicast_options.into_bits(),
-                    &mut offset,
-                    Endian::Little,
-                )?;
-                buf.gwrite_with(unicast_options_ext, &mut offset, Endian::Little)?;
-            }
-            (None, Some(unicast_options_ext)) => {
-                buf.gwrite(8u16, &mut offset)?;
-                buf.gwrite_with(0u32, &mut offset, Endian::Little)?;
-                buf.gwrite_with(unicast_options_ext, &mut offset, Endian::Little)?;
-            }
-            _ => {}
+        if let Some(unicast_options) = self.unicast_options {
+            buf.gwrite(unicast_options, &mut offset)?;
         }
         if let Some(extended_flags) = extended_flags {
             buf.gwrite_with(
@@ -326,11 +313,127 @@ impl TryIntoCtx for DataPathStateTLV {
                 buf.gwrite(stats, &mut offset)?;
             }
         }
+        buf.gwrite(self.trailing, &mut offset)?;
 
         Ok(offset)
     }
 }
 
+/// A builder for the [DataPathStateTLV].
+///
+/// The defaults were taken from a MIF sent by macOS.
+/// The presence flags are derived from the fields, which were set.
+pub struct DataPathStateTLVBuilder<'a> {
+    tlv: DataPathStateTLV<'a>,
+}
+impl<'a> DataPathStateTLVBuilder<'a> {
+    /// Create a new builder with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    builder_setters!(
+        /// Set the flags.
+        ///
+        /// The presence flags will be overwritten by [build](Self::build).
+        flags: DataPathFlags,
+        /// Set the bytes following the known fields.
+        trailing: &'a [u8]
+    );
+    builder_setters!(
+        optional
+        /// Set the extended flags.
+        ///
+        /// The presence flags will be overwritten by [build](Self::build).
+        extended_flags: DataPathExtendedFlags,
+        /// Set the channel map.
+        channel_map: DataPathChannel,
+        /// Set the BSSID and channel of the infrastructure network.
+        infra_bssid_channel: (MACAddress, u16),
+        /// Set the address used in the infrastructure network.
+        infra_address: MACAddress,
+        /// Set the address used for AWDL.
+        awdl_address: MACAddress,
+        /// Set the unicast options.
+        unicast_options: DataPathUnicastOptions<'a>,
+        /// Set the RLFC.
+        rlfc: u32,
+        /// Set the log trigger ID.
+        log_trigger_id: u16,
+        /// Set the statistics.
+        stats: DataPathStats
+    );
+    /// Set the country code.
+    ///
+    /// The code is validated by [build](Self::build).
+    pub fn country_code(mut self, country_code: CountryCode) -> Self {
+        self.tlv.country_code = Some(country_code);
+        self
+    }
+    /// Validate the fields, set the presence flags and return the TLV.
+    pub fn build(self) -> Result<DataPathStateTLV<'a>, scroll::Error> {
+        let mut tlv = self.tlv;
+        if let Some(country_code) = tlv.country_code {
+            if !country_code.is_valid() {
+                return Err(scroll::Error::BadInput {
+                    size: 0,
+                    msg: "Country code isn't an ISO 3166-1 alpha-2 code.",
+                });
+            }
+        }
+
+        let social_channel_map_present = tlv
+            .channel_map
+            .is_some_and(|channel_map| channel_map.is_social_channel_map());
+        if tlv.log_trigger_id.is_some()
+            || tlv.rlfc.is_some()
+            || tlv.stats.is_some()
+            || social_channel_map_present
+        {
+            let mut extended_flags = tlv.extended_flags.unwrap_or_default();
+
+            extended_flags.log_trigger_id_present = tlv.log_trigger_id.is_some();
+            extended_flags.rlfc_present = tlv.rlfc.is_some();
+            extended_flags.stats_present = tlv.stats.is_some();
+            tlv.extended_flags = Some(extended_flags);
+        }
+        if let (Some(extended_flags), Some(channel_map)) =
+            (tlv.extended_flags.as_mut(), tlv.channel_map)
+        {
+            extended_flags.is_social_channel_map_supported = channel_map.is_social_channel_map();
+        }
+
+        tlv.flags.country_code_present = tlv.country_code.is_some();
+        tlv.flags.channel_map_present = tlv.channel_map.is_some();
+        tlv.flags.infra_bssid_channel_present = tlv.infra_bssid_channel.is_some();
+        tlv.flags.infra_address_present = tlv.infra_address.is_some();
+        tlv.flags.awdl_address_present = tlv.awdl_address.is_some();
+        tlv.flags.unicast_options_present = tlv.unicast_options.is_some();
+        tlv.flags.extended_flags = tlv.extended_flags.is_some();
+
+        Ok(tlv)
+    }
+}
+impl Default for DataPathStateTLVBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            tlv: DataPathStateTLV {
+                flags: DataPathFlags {
+                    dualband_support: true,
+                    airplay_solo_mode_support: true,
+                    umi_support: true,
+                    ..Default::default()
+                },
+                extended_flags: Some(DataPathExtendedFlags {
+                    is_social_channel_map_supported: true,
+                    dfs_proxy_support: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[test]
 fn test_data_path_state_tlv() {
     use self::misc::ChannelMap;
@@ -354,19 +457,17 @@ fn test_data_path_state_tlv() {
                 extended_flags: true,
                 ..Default::default()
             },
-            country_code: Some(['D', 'E']),
+            country_code: CountryCode::new("DE", CountryEnvironment::Unspecified),
             channel_map: Some(DataPathChannel::ChannelMap(ChannelMap {
                 channel_6: true,
                 channel_44: true,
-                channel_149: false
+                channel_149: false,
+                unknown: 0
             })),
             infra_bssid_channel: Some((ZERO, 0)),
             infra_address: Some(MACAddress::new([0xbe, 0x45, 0xa1, 0xd1, 0x49, 0xb6])),
             awdl_address: None,
-            unicast_options: Some(UnicastOptions {
-                ..Default::default()
-            },),
-            unicast_options_ext: None,
+            unicast_options: Some(UnicastOptions::default().into()),
             extended_flags: Some(DataPathExtendedFlags {
                 log_trigger_id_present: true,
                 rlfc_present: true,
@@ -382,9 +483,88 @@ fn test_data_path_state_tlv() {
                 aw_seq_counter: 0,
                 pay_update_coutner: 32641,
             }),
+            trailing: &[],
         }
     );
     let mut buf = vec![0x00u8; data_path_state.size_in_bytes()];
     buf.pwrite(data_path_state, 0).unwrap();
     assert_eq!(bytes, buf.as_slice());
+
+    // Fields appended by newer versions have to survive a round trip.
+    let mut extended_bytes = bytes.to_vec();
+    extended_bytes.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    let extended_data_path_state = extended_bytes.pread::<DataPathStateTLV>(0).unwrap();
+    assert_eq!(extended_data_path_state.trailing, &[0xde, 0xad, 0xbe, 0xef]);
+    let mut buf = vec![0x00u8; extended_data_path_state.size_in_bytes()];
+    buf.pwrite(extended_data_path_state, 0).unwrap();
+    assert_eq!(extended_bytes, buf);
+}
+#[cfg(test)]
+#[test]
+fn test_data_path_state_tlv_builder() {
+    use mac_parser::ZERO;
+
+    let data_path_state = DataPathStateTLVBuilder::new()
+        .country_code(CountryCode::new("DE", CountryEnvironment::Unspecified).unwrap())
+        .channel_map(DataPathChannel::ChannelMap(ChannelMap {
+            channel_6: true,
+            channel_44: true,
+            channel_149: false,
+            unknown: 0,
+        }))
+        .infra_bssid_channel((ZERO, 0))
+        .infra_address(MACAddress::new([0xbe, 0x45, 0xa1, 0xd1, 0x49, 0xb6]))
+        .unicast_options(UnicastOptions::default().into())
+        .rlfc(10836)
+        .log_trigger_id(0x00)
+        .stats(DataPathStats {
+            msec_since_activation: 183,
+            aw_seq_counter: 0,
+            pay_update_coutner: 32641,
+        })
+        .build()
+        .unwrap();
+    let bytes = include_bytes!("../../../../test_bins/data_path_state_tlv.bin");
+    assert_eq!(data_path_state, bytes.pread::<DataPathStateTLV>(0).unwrap());
+
+    // The default extended flags claim support for the social channel map, which has to be cleared.
+    let data_path_state = DataPathStateTLVBuilder::new()
+        .channel_map(DataPathChannel::SingleChannel {
+            channel: 149,
+            unknown: 0,
+        })
+        .build()
+        .unwrap();
+    let mut buf = [0x00u8; 0x10];
+    let length = buf.pwrite(data_path_state, 0).unwrap();
+    assert_eq!(
+        buf[..length].pread::<DataPathStateTLV>(0).unwrap(),
+        data_path_state
+    );
+
+    let data_path_state = DataPathStateTLVBuilder::new()
+        .unicast_options(DataPathUnicastOptions {
+            options: UnicastOptions {
+                start_sidecar: true,
+                ..Default::default()
+            },
+            extended: Some((UnicastOptionsExt::from_bits(0x1), &[0xaa])),
+        })
+        .build()
+        .unwrap();
+    let mut buf = [0x00u8; 0x20];
+    let length = buf.pwrite(data_path_state, 0).unwrap();
+    assert_eq!(length, data_path_state.size_in_bytes());
+    assert_eq!(
+        buf[..length].pread::<DataPathStateTLV>(0).unwrap(),
+        data_path_state
+    );
+
+    assert!(DataPathStateTLVBuilder::new()
+        .country_code(CountryCode {
+            code: *b"de",
+            environment: CountryEnvironment::Any,
+        })
+        .build()
+        .is_err());
 }
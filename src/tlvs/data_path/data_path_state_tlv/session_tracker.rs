@@ -0,0 +1,220 @@
+use core::time::Duration;
+
+use super::{DataPathStateTLV, UnicastOptions};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A session, which is started and stopped through the [unicast options](UnicastOptions).
+pub enum UnicastSession {
+    AirPlay,
+    Sidecar,
+    RealTimeMode,
+    HtMode,
+    RtgEnsemble,
+    AirPlayInRtgMode,
+    SidecarInRtgMode,
+    RemoteCamera,
+}
+impl UnicastSession {
+    const COUNT: usize = 8;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// An event signaled through the [unicast options](UnicastOptions).
+pub enum UnicastEventKind {
+    SessionStarted(UnicastSession),
+    SessionStopped(UnicastSession),
+    CacheRequest,
+    JumpstartDfsProxy,
+    AirPlayOnDfsChannel,
+    SidecarBackgroundRequest,
+    SidecarForegroundRequest,
+    MultiPeerSteeringStarted,
+    MultiPeerSteeringFailed,
+    AirPlayRecoveryStarted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// An event emitted by the [UnicastSessionTracker].
+pub struct UnicastEvent {
+    /// The timestamp of the [DataPathStateTLV], in which the event was first signaled.
+    pub timestamp: Duration,
+    pub kind: UnicastEventKind,
+}
+
+/// Returns an iterator over the events signaled by the set fields of the [unicast options](UnicastOptions).
+fn unicast_events(options: UnicastOptions) -> impl Iterator<Item = UnicastEventKind> + Clone {
+    use UnicastEventKind::*;
+    use UnicastSession::*;
+    [
+        (options.start_airplay, SessionStarted(AirPlay)),
+        (options.cache_request, CacheRequest),
+        (options.jumpstart_dfs_proxy, JumpstartDfsProxy),
+        (options.airplay_on_dfs_channel, AirPlayOnDfsChannel),
+        (options.start_sidecar, SessionStarted(Sidecar)),
+        (options.sidecar_bg_request, SidecarBackgroundRequest),
+        (options.sidecar_fg_request, SidecarForegroundRequest),
+        (options.stop_sidecar, SessionStopped(Sidecar)),
+        (options.start_multi_peer_steering, MultiPeerSteeringStarted),
+        (options.start_real_time_mode, SessionStarted(RealTimeMode)),
+        (options.stop_real_time_mode, SessionStopped(RealTimeMode)),
+        (options.start_airplay_recovery, AirPlayRecoveryStarted),
+        (options.start_ht_mode, SessionStarted(HtMode)),
+        (options.stop_ht_mode, SessionStopped(HtMode)),
+        (options.stop_airplay, SessionStopped(AirPlay)),
+        (options.failed_multi_peer_steering, MultiPeerSteeringFailed),
+        (options.start_rtg_ensemble, SessionStarted(RtgEnsemble)),
+        (options.stop_rtg_ensemble, SessionStopped(RtgEnsemble)),
+        (
+            options.start_airplay_in_rtg_mode,
+            SessionStarted(AirPlayInRtgMode),
+        ),
+        (
+            options.stop_airplay_in_rtg_mode,
+            SessionStopped(AirPlayInRtgMode),
+        ),
+        (
+            options.start_sidecar_in_rtg_mode,
+            SessionStarted(SidecarInRtgMode),
+        ),
+        (
+            options.stop_sidecar_in_rtg_mode,
+            SessionStopped(SidecarInRtgMode),
+        ),
+        (options.start_remote_camera, SessionStarted(RemoteCamera)),
+        (options.stop_remote_camera, SessionStopped(RemoteCamera)),
+    ]
+    .into_iter()
+    .filter_map(|(set, kind)| set.then_some(kind))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// Tracks the unicast options of a peer across its [DataPathStateTLV]s.
+///
+/// Since a peer repeats the same options in every frame, an event is only emitted, when its bit gets set.
+pub struct UnicastSessionTracker {
+    previous_options: u32,
+    session_starts: [Option<Duration>; UnicastSession::COUNT],
+}
+impl UnicastSessionTracker {
+    /// Create a new tracker, with no active sessions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Process the next [DataPathStateTLV] of the peer, which was received at the timestamp.
+    ///
+    /// Returns an iterator over the newly signaled events.
+    /// If the TLV carries no unicast options, all bits are considered cleared.
+    pub fn update(
+        &mut self,
+        timestamp: Duration,
+        data_path_state: &DataPathStateTLV,
+    ) -> impl Iterator<Item = UnicastEvent> + Clone {
+        let options = data_path_state
+            .unicast_options
+            .map(|unicast_options| unicast_options.options.into_bits())
+            .unwrap_or_default();
+        let newly_set = options & !self.previous_options;
+        self.previous_options = options;
+
+        let events = unicast_events(UnicastOptions::from_bits(newly_set))
+            .map(move |kind| UnicastEvent { timestamp, kind });
+        for event in events.clone() {
+            match event.kind {
+                UnicastEventKind::SessionStarted(session) => {
+                    self.session_starts[session as usize].get_or_insert(timestamp);
+                }
+                UnicastEventKind::SessionStopped(session) => {
+                    self.session_starts[session as usize] = None;
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+    /// Check if the session was started and not stopped yet.
+    pub fn is_active(&self, session: UnicastSession) -> bool {
+        self.session_starts[session as usize].is_some()
+    }
+    /// Returns the timestamp, at which the session was started, if it's active.
+    pub fn session_start(&self, session: UnicastSession) -> Option<Duration> {
+        self.session_starts[session as usize]
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_unicast_session_tracker() {
+    use alloc::vec::Vec;
+
+    let data_path_state = |options: Option<UnicastOptions>| DataPathStateTLV {
+        unicast_options: options.map(Into::into),
+        ..Default::default()
+    };
+    let mut tracker = UnicastSessionTracker::new();
+
+    let airplay_started = data_path_state(Some(UnicastOptions {
+        start_airplay: true,
+        ..Default::default()
+    }));
+    assert_eq!(
+        tracker
+            .update(Duration::from_millis(100), &airplay_started)
+            .collect::<Vec<_>>(),
+        [UnicastEvent {
+            timestamp: Duration::from_millis(100),
+            kind: UnicastEventKind::SessionStarted(UnicastSession::AirPlay),
+        }]
+    );
+    // The same options are repeated, which mustn't yield new events.
+    assert_eq!(
+        tracker
+            .update(Duration::from_millis(200), &airplay_started)
+            .count(),
+        0
+    );
+    assert_eq!(
+        tracker.session_start(UnicastSession::AirPlay),
+        Some(Duration::from_millis(100))
+    );
+
+    let events = tracker
+        .update(
+            Duration::from_millis(300),
+            &data_path_state(Some(UnicastOptions {
+                stop_airplay: true,
+                sidecar_fg_request: true,
+                start_sidecar: true,
+                ..Default::default()
+            })),
+        )
+        .map(|event| event.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            UnicastEventKind::SessionStarted(UnicastSession::Sidecar),
+            UnicastEventKind::SidecarForegroundRequest,
+            UnicastEventKind::SessionStopped(UnicastSession::AirPlay),
+        ]
+    );
+    assert!(!tracker.is_active(UnicastSession::AirPlay));
+    assert!(tracker.is_active(UnicastSession::Sidecar));
+
+    assert_eq!(
+        tracker
+            .update(Duration::from_millis(400), &data_path_state(None))
+            .count(),
+        0
+    );
+    assert!(tracker
+        .update(
+            Duration::from_millis(500),
+            &data_path_state(Some(UnicastOptions {
+                stop_rtg_ensemble: true,
+                ..Default::default()
+            })),
+        )
+        .eq([UnicastEvent {
+            timestamp: Duration::from_millis(500),
+            kind: UnicastEventKind::SessionStopped(UnicastSession::RtgEnsemble),
+        }]));
+}
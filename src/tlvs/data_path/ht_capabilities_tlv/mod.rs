@@ -64,6 +64,61 @@ impl TryIntoCtx for HTCapabilitiesTLV {
         Ok(offset)
     }
 }
+/// A builder for the [HTCapabilitiesTLV].
+///
+/// The defaults were taken from a MIF sent by macOS.
+pub struct HTCapabilitiesTLVBuilder {
+    tlv: HTCapabilitiesTLV,
+}
+impl HTCapabilitiesTLVBuilder {
+    /// Create a new builder with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    builder_setters!(
+        ht_capabilities_info: HTCapabilitiesInfo,
+        a_mpdu_parameters: AMpduParameters,
+        rx_spatial_stream_count: u8
+    );
+    /// Validate the capabilities and return the TLV.
+    ///
+    /// If a short guard interval for 40MHz is supported, the 40MHz channel width support is set automatically.
+    pub fn build(self) -> Result<HTCapabilitiesTLV, scroll::Error> {
+        let mut tlv = self.tlv;
+        if !(1..=4).contains(&tlv.rx_spatial_stream_count) {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "HT only supports one to four spatial streams.",
+            });
+        }
+        if tlv.ht_capabilities_info.short_gi_40mhz {
+            tlv.ht_capabilities_info.support_channel_width = true;
+        }
+        Ok(tlv)
+    }
+}
+impl Default for HTCapabilitiesTLVBuilder {
+    fn default() -> Self {
+        Self {
+            tlv: HTCapabilitiesTLV {
+                ht_capabilities_info: HTCapabilitiesInfo {
+                    ldpc_coding_capability: true,
+                    support_channel_width: true,
+                    sm_power_save: SmPwSave::Disabled,
+                    short_gi_20mhz: true,
+                    short_gi_40mhz: true,
+                    rx_stbc: 1,
+                    ..Default::default()
+                },
+                a_mpdu_parameters: AMpduParameters {
+                    max_a_mpdu_length: MAXAMpduLength::VeryLarge,
+                    mpdu_density: MpduDensity::Sixteen,
+                },
+                rx_spatial_stream_count: 2,
+            },
+        }
+    }
+}
 #[cfg(test)]
 #[test]
 fn test_ht_capabilities() {
@@ -102,3 +157,30 @@ fn test_ht_capabilities() {
     buf.as_mut_slice().pwrite(ht_capabilities_tlv, 0).unwrap();
     assert_eq!(buf, bytes);
 }
+#[cfg(test)]
+#[test]
+fn test_ht_capabilities_builder() {
+    use scroll::Pread;
+
+    let bytes = &include_bytes!("../../../../test_bins/ht_capabilities_tlv.bin")[3..];
+    assert_eq!(
+        HTCapabilitiesTLVBuilder::new().build().unwrap(),
+        bytes.pread::<HTCapabilitiesTLV>(0).unwrap()
+    );
+    let ht_capabilities_tlv = HTCapabilitiesTLVBuilder::new()
+        .ht_capabilities_info(HTCapabilitiesInfo {
+            short_gi_40mhz: true,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    assert!(
+        ht_capabilities_tlv
+            .ht_capabilities_info
+            .support_channel_width
+    );
+    assert!(HTCapabilitiesTLVBuilder::new()
+        .rx_spatial_stream_count(0)
+        .build()
+        .is_err());
+}
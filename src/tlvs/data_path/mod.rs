@@ -3,5 +3,7 @@ mod ht_capabilities_tlv;
 mod ieee80211_cntr_tlv;
 
 pub use data_path_state_tlv::*;
-pub use ht_capabilities_tlv::{ampdu_parameters, ht_capabilities_info, HTCapabilitiesTLV};
+pub use ht_capabilities_tlv::{
+    ampdu_parameters, ht_capabilities_info, HTCapabilitiesTLV, HTCapabilitiesTLVBuilder,
+};
 pub use ieee80211_cntr_tlv::IEEE80211ContainerTLV;
@@ -1,19 +1,83 @@
+use core::{
+    hash::{Hash, Hasher},
+    net::Ipv6Addr,
+};
+
+use mac_parser::MACAddress;
 use scroll::{
     ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
     Pread, Pwrite,
 };
 
 use crate::{
-    common::{AWDLDnsName, AWDLStr, ReadLabelIterator},
-    tlvs::{AWDLTLVType, AwdlTlv},
+    common::{AWDLDnsCompression, AWDLDnsName, AWDLStr, ReadLabelIterator},
+    tlvs::{data_path::DataPathStateTLV, AWDLTLVType, AwdlTlv},
 };
 
-#[derive(Clone, Copy, Debug, Default, Hash)]
+/// The flags sent by macOS. Their meaning is unknown.
+pub const DEFAULT_ARPA_FLAGS: u8 = 0x03;
+
+#[derive(Clone, Copy, Debug)]
 /// A TLV containing the hostname of the peer. Used for reverse DNS.
 pub struct ArpaTLV<I> {
+    /// The flags preceding the hostname, which are [DEFAULT_ARPA_FLAGS] in all observed frames.
+    pub flags: u8,
     /// The actual arpa data.
     pub arpa: AWDLDnsName<I>,
 }
+impl<I: Default> Default for ArpaTLV<I> {
+    fn default() -> Self {
+        Self {
+            flags: DEFAULT_ARPA_FLAGS,
+            arpa: AWDLDnsName::default(),
+        }
+    }
+}
+impl<'a> ArpaTLV<HostnameLabels<'a>> {
+    /// Create the TLV from a hostname like `simon-framework.local`.
+    ///
+    /// A trailing `.local` is replaced by the [Local](AWDLDnsCompression::Local) compression, which is also used, if the hostname doesn't end in it.
+    /// An error is returned, if no label precedes the domain or a label is empty or longer than 63 bytes.
+    pub fn from_hostname(hostname: &'a str) -> Result<Self, scroll::Error> {
+        let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+        let hostname = hostname.strip_suffix(".local").unwrap_or(hostname);
+        if hostname.eq_ignore_ascii_case("local") {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Hostname has no label before the domain.",
+            });
+        }
+        if hostname
+            .split('.')
+            .any(|label| label.is_empty() || label.len() > 63)
+        {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "DNS labels must be between 1 and 63 bytes long.",
+            });
+        }
+        Ok(Self {
+            flags: DEFAULT_ARPA_FLAGS,
+            arpa: AWDLDnsName {
+                labels: HostnameLabels(hostname),
+                domain: AWDLDnsCompression::Local,
+            },
+        })
+    }
+}
+impl<I: Clone> ArpaTLV<I> {
+    /// Derive the link-local address and the `ip6.arpa` name of the peer, from the AWDL address in its [DataPathStateTLV].
+    ///
+    /// The returned [ReverseLookup] contains everything needed for a PTR record, pointing at the hostname.
+    pub fn reverse_lookup(&self, data_path_state: &DataPathStateTLV) -> Option<ReverseLookup<I>> {
+        let address = link_local_address(data_path_state.awdl_address?);
+        Some(ReverseLookup {
+            address,
+            ptr_name: ip6_arpa_name(address),
+            hostname: self.arpa.clone(),
+        })
+    }
+}
 impl<I> AwdlTlv for ArpaTLV<I> {
     const TLV_TYPE: AWDLTLVType = AWDLTLVType::Arpa;
 }
@@ -24,7 +88,13 @@ where
     RhsIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
 {
     fn eq(&self, other: &ArpaTLV<RhsIterator>) -> bool {
-        self.arpa == other.arpa
+        self.flags == other.flags && self.arpa == other.arpa
+    }
+}
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for ArpaTLV<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.flags.hash(state);
+        self.arpa.hash(state);
     }
 }
 impl<'a, I> MeasureWith<()> for ArpaTLV<I>
@@ -39,9 +109,9 @@ impl<'a> TryFromCtx<'a> for ArpaTLV<ReadLabelIterator<'a>> {
     type Error = scroll::Error;
     fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
         let mut offset = 0;
-        offset += 1; // Skip flags.
+        let flags = from.gread(&mut offset)?;
         let arpa = from.gread(&mut offset)?;
-        Ok((Self { arpa }, offset))
+        Ok((Self { flags, arpa }, offset))
     }
 }
 impl<'a, I> TryIntoCtx for ArpaTLV<I>
@@ -51,7 +121,7 @@ where
     type Error = scroll::Error;
     fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
         let mut offset = 0;
-        buf.gwrite(0x03u8, &mut offset)?;
+        buf.gwrite(self.flags, &mut offset)?;
         buf.gwrite(self.arpa, &mut offset)?;
 
         Ok(offset)
@@ -59,6 +129,86 @@ where
 }
 /// The default arpa tlv returned by reading.
 pub type DefaultArpaTLV<'a> = ArpaTLV<ReadLabelIterator<'a>>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The labels of a hostname, which are separated by dots.
+pub struct HostnameLabels<'a>(pub &'a str);
+impl<'a> IntoIterator for HostnameLabels<'a> {
+    type Item = AWDLStr<'a>;
+    type IntoIter = core::iter::Map<core::str::Split<'a, char>, fn(&'a str) -> AWDLStr<'a>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0
+            .split('.')
+            .map(AWDLStr::from as fn(&'a str) -> AWDLStr<'a>)
+    }
+}
+
+/// The hexadecimal digits, which are used as the labels of an `ip6.arpa` name.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The labels of an `ip6.arpa` name, which are the nibbles of the address in reverse order.
+pub struct Ip6ArpaLabels {
+    octets: [u8; 16],
+    nibble: usize,
+}
+impl Iterator for Ip6ArpaLabels {
+    type Item = AWDLStr<'static>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.nibble >= 32 {
+            return None;
+        }
+        let octet = self.octets[15 - self.nibble / 2];
+        // The low nibble of every octet comes first.
+        let digit = (octet >> (self.nibble % 2 * 4) & 0xf) as usize;
+        self.nibble += 1;
+        Some(AWDLStr(&HEX_DIGITS[digit..digit + 1]))
+    }
+}
+
+/// Derive the IPv6 link-local address from the MAC address, using the modified EUI-64 format.
+pub fn link_local_address(mac_address: MACAddress) -> Ipv6Addr {
+    let [a, b, c, d, e, f] = mac_address.0;
+    // The universal/local bit is inverted and 0xfffe is inserted in the middle.
+    Ipv6Addr::new(
+        0xfe80,
+        0,
+        0,
+        0,
+        u16::from_be_bytes([a ^ 0x02, b]),
+        u16::from_be_bytes([c, 0xff]),
+        u16::from_be_bytes([0xfe, d]),
+        u16::from_be_bytes([e, f]),
+    )
+}
+/// Returns the `ip6.arpa` name of the address, which is used for reverse DNS.
+pub fn ip6_arpa_name(address: Ipv6Addr) -> AWDLDnsName<Ip6ArpaLabels> {
+    AWDLDnsName {
+        labels: Ip6ArpaLabels {
+            octets: address.octets(),
+            nibble: 0,
+        },
+        domain: AWDLDnsCompression::Ip6Arpa,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// The addresses of a peer, derived by [ArpaTLV::reverse_lookup].
+pub struct ReverseLookup<I> {
+    /// The IPv6 link-local address of the peer.
+    pub address: Ipv6Addr,
+    /// The `ip6.arpa` name of the address.
+    pub ptr_name: AWDLDnsName<Ip6ArpaLabels>,
+    /// The hostname, which the PTR record points at.
+    pub hostname: AWDLDnsName<I>,
+}
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for ReverseLookup<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+        self.ptr_name.hash(state);
+        self.hostname.hash(state);
+    }
+}
 #[cfg(test)]
 #[test]
 fn test_arpa_tlv() {
@@ -72,15 +222,60 @@ fn test_arpa_tlv() {
     assert_eq!(
         arpa_tlv,
         ArpaTLV {
+            flags: DEFAULT_ARPA_FLAGS,
             arpa: AWDLDnsName {
                 labels: ["simon-framework".into()],
                 domain: AWDLDnsCompression::Local
             }
         }
     );
+    assert_eq!(
+        ArpaTLV::from_hostname("simon-framework.local.").unwrap(),
+        arpa_tlv
+    );
+    assert_eq!(ArpaTLV::from_hostname("simon-framework").unwrap(), arpa_tlv);
+    let long_label = "a".repeat(64);
+    for hostname in [
+        "a..local",
+        "local",
+        "local.",
+        "",
+        ".",
+        "a.local..",
+        &long_label,
+    ] {
+        assert!(ArpaTLV::from_hostname(hostname).is_err());
+    }
     let mut buf = vec![0x00; arpa_tlv.measure_with(&())];
     buf.as_mut_slice()
         .pwrite::<ArpaTLV<ReadLabelIterator>>(arpa_tlv, 0)
         .unwrap();
     assert_eq!(buf, bytes);
 }
+#[cfg(test)]
+#[test]
+fn test_reverse_lookup() {
+    use alloc::string::ToString;
+
+    let data_path_state = DataPathStateTLV {
+        awdl_address: Some(MACAddress::new([0xbc, 0x45, 0xa1, 0xd1, 0x49, 0xb6])),
+        ..Default::default()
+    };
+    let reverse_lookup = ArpaTLV::from_hostname("simon-framework.local")
+        .unwrap()
+        .reverse_lookup(&data_path_state)
+        .unwrap();
+    assert_eq!(
+        reverse_lookup.address,
+        Ipv6Addr::new(0xfe80, 0, 0, 0, 0xbe45, 0xa1ff, 0xfed1, 0x49b6)
+    );
+    assert_eq!(
+        reverse_lookup.ptr_name.to_string(),
+        "6.b.9.4.1.d.e.f.f.f.1.a.5.4.e.b.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.f.ip6.arpa"
+    );
+    assert_eq!(reverse_lookup.hostname.to_string(), "simon-framework.local");
+    assert!(ArpaTLV::from_hostname("simon-framework")
+        .unwrap()
+        .reverse_lookup(&DataPathStateTLV::default())
+        .is_none());
+}
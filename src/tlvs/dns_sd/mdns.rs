@@ -0,0 +1,384 @@
+use core::{
+    hash::{Hash, Hasher},
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use scroll::{Pread, Pwrite, NETWORK};
+
+use crate::common::{AWDLDnsName, AWDLStr, WireLabelIterator, MAX_WIRE_NAME_LENGTH};
+
+use super::{dns_record::AWDLDnsRecordType, ArpaTLV, ServiceResponseTLV};
+
+/// The UDP port used by mDNS.
+pub const MDNS_PORT: u16 = 5353;
+/// The IPv4 multicast address used by mDNS.
+pub const MDNS_IPV4_ADDRESS: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+/// The IPv6 multicast address used by mDNS.
+pub const MDNS_IPV6_ADDRESS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+
+/// The length of the DNS header.
+const HEADER_LENGTH: usize = 12;
+/// The flags of an authoritative response.
+const RESPONSE_FLAGS: u16 = 0x8400;
+/// The DNS record type of an IPv6 address.
+const AAAA_RECORD_TYPE: u16 = AWDLDnsRecordType::AAAA.into_bits() as u16;
+/// The IN class.
+const CLASS_IN: u16 = 0x0001;
+/// Signals, that the record replaces all cached records with the same name, type and class.
+const CACHE_FLUSH: u16 = 0x8000;
+
+/// Writes an mDNS response, containing the records from AWDL, into a buffer.
+///
+/// Names are written uncompressed.
+pub struct MdnsResponseWriter<'buf> {
+    buf: &'buf mut [u8],
+    offset: usize,
+    answer_count: u16,
+}
+impl<'buf> MdnsResponseWriter<'buf> {
+    /// Create a new writer, which starts the response at the beginning of the buffer.
+    pub fn new(buf: &'buf mut [u8]) -> Result<Self, scroll::Error> {
+        let mut offset = 0;
+        buf.gwrite_with(0u16, &mut offset, NETWORK)?; // ID
+        buf.gwrite_with(RESPONSE_FLAGS, &mut offset, NETWORK)?;
+        // The counts will be inserted, when finishing the response.
+        buf.gwrite([0x00u8; 8], &mut offset)?;
+        Ok(Self {
+            buf,
+            offset,
+            answer_count: 0,
+        })
+    }
+    /// Append a record, which is only written, if it fits completely and the RDATA matches its length.
+    fn push_record<'a, I>(
+        &mut self,
+        name: &AWDLDnsName<I>,
+        record_type: u16,
+        cache_flush: bool,
+        ttl: u32,
+        rdata_length: usize,
+        write_rdata: impl FnOnce(&mut [u8]) -> Result<usize, scroll::Error>,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let answer_count = self
+            .answer_count
+            .checked_add(1)
+            .ok_or(scroll::Error::TooBig {
+                size: u16::MAX as usize,
+                len: self.answer_count as usize + 1,
+            })?;
+        let rdata_length = u16::try_from(rdata_length).map_err(|_| scroll::Error::TooBig {
+            size: u16::MAX as usize,
+            len: rdata_length,
+        })?;
+        let mut offset = self.offset;
+        offset += name.write_wire(&mut self.buf[offset..])?;
+        self.buf.gwrite_with(record_type, &mut offset, NETWORK)?;
+        self.buf.gwrite_with(
+            if cache_flush {
+                CLASS_IN | CACHE_FLUSH
+            } else {
+                CLASS_IN
+            },
+            &mut offset,
+            NETWORK,
+        )?;
+        self.buf.gwrite_with(ttl, &mut offset, NETWORK)?;
+        self.buf.gwrite_with(rdata_length, &mut offset, NETWORK)?;
+        let written = write_rdata(&mut self.buf[offset..])?;
+        if written != rdata_length as usize {
+            return Err(scroll::Error::BadInput {
+                size: written,
+                msg: "Written RDATA doesn't match the RDLENGTH.",
+            });
+        }
+        offset += written;
+
+        self.offset = offset;
+        self.answer_count = answer_count;
+        Ok(())
+    }
+    /// Append the record of the service response.
+    ///
+    /// All records except PTR records, which are shared, are marked as unique.
+    pub fn push_service_response<'a, I>(
+        &mut self,
+        service_response: &ServiceResponseTLV<'a, I>,
+        ttl: u32,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let record_type = service_response.record.record_type();
+        self.push_record(
+            &service_response.name,
+            record_type.into_bits() as u16,
+            record_type != AWDLDnsRecordType::PTR,
+            ttl,
+            service_response.record.wire_rdata_length(),
+            |buf| service_response.record.write_wire_rdata(buf),
+        )
+    }
+    /// Append an AAAA record with the address of the host.
+    pub fn push_address<'a, I>(
+        &mut self,
+        host: &AWDLDnsName<I>,
+        address: Ipv6Addr,
+        ttl: u32,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        self.push_record(host, AAAA_RECORD_TYPE, true, ttl, 16, |buf| {
+            buf.pwrite(address.octets(), 0)
+        })
+    }
+    /// Append an AAAA record with the address of the host from the [ArpaTLV].
+    pub fn push_arpa<'a, I>(
+        &mut self,
+        arpa: &ArpaTLV<I>,
+        address: Ipv6Addr,
+        ttl: u32,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        self.push_address(&arpa.arpa, address, ttl)
+    }
+    /// Insert the number of answers and return the length of the response.
+    pub fn finish(self) -> usize {
+        self.buf[6..8].copy_from_slice(&self.answer_count.to_be_bytes());
+        self.offset
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// A question of an mDNS query, which can be answered by a [ServiceResponseTLV].
+///
+/// The TLV carrying AWDL service requests hasn't been decoded yet, so questions aren't translated into them.
+/// Instead, they can be [matched](Self::is_answered_by) against the service responses already received from peers.
+pub struct MdnsQuestion<I> {
+    pub name: AWDLDnsName<I>,
+    pub record_type: AWDLDnsRecordType,
+}
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for MdnsQuestion<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.record_type.hash(state);
+    }
+}
+impl<'a, I> MdnsQuestion<I>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    /// Check if the service response answers the question.
+    ///
+    /// The names are compared in wire format and case insensitively, so the chosen compression doesn't matter.
+    pub fn is_answered_by<'b, J>(&self, service_response: &ServiceResponseTLV<'b, J>) -> bool
+    where
+        J: IntoIterator<Item = AWDLStr<'b>> + Clone,
+    {
+        if self.record_type != service_response.record.record_type() {
+            return false;
+        }
+        let mut question_name = [0x00; MAX_WIRE_NAME_LENGTH];
+        let mut response_name = [0x00; MAX_WIRE_NAME_LENGTH];
+        match (
+            self.name.write_wire(&mut question_name),
+            service_response.name.write_wire(&mut response_name),
+        ) {
+            (Ok(question_length), Ok(response_length)) => question_name[..question_length]
+                .eq_ignore_ascii_case(&response_name[..response_length]),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// An mDNS query, which was validated on creation.
+pub struct MdnsQuery<'a> {
+    message: &'a [u8],
+    question_count: u16,
+}
+impl<'a> MdnsQuery<'a> {
+    /// Validate the header and all questions of the query.
+    pub fn new(message: &'a [u8]) -> Result<Self, scroll::Error> {
+        let flags = message.pread_with::<u16>(2, NETWORK)?;
+        if flags & 0x8000 != 0 {
+            return Err(scroll::Error::BadInput {
+                size: 2,
+                msg: "DNS message isn't a query.",
+            });
+        }
+        let question_count = message.pread_with(4, NETWORK)?;
+        let mut offset = HEADER_LENGTH;
+        for _ in 0..question_count {
+            offset += AWDLDnsName::from_wire(message, offset)?.1;
+            // Type and class.
+            message.pread_with::<u32>(offset, NETWORK)?;
+            offset += 4;
+        }
+        Ok(Self {
+            message,
+            question_count,
+        })
+    }
+    /// Returns an iterator over the questions, which can be answered through AWDL.
+    ///
+    /// Questions for other record types are skipped.
+    pub fn questions(
+        &self,
+    ) -> impl Iterator<Item = MdnsQuestion<WireLabelIterator<'a>>> + Clone + 'a {
+        let message = self.message;
+        let mut offset = HEADER_LENGTH;
+        (0..self.question_count).filter_map(move |_| {
+            let (name, length) = AWDLDnsName::from_wire(message, offset).ok()?;
+            offset += length;
+            let record_type = message.pread_with::<u16>(offset, NETWORK).ok()?;
+            offset += 4;
+            let record_type = AWDLDnsRecordType::from_bits(u8::try_from(record_type).ok()?);
+            (!matches!(record_type, AWDLDnsRecordType::Unknown(_)))
+                .then_some(MdnsQuestion { name, record_type })
+        })
+    }
+}
+#[cfg(test)]
+mod mdns_tests {
+    use core::net::Ipv6Addr;
+
+    use scroll::{Pread, Pwrite, NETWORK};
+
+    use super::{MdnsQuery, MdnsResponseWriter};
+    use crate::{
+        common::{AWDLDnsCompression, AWDLDnsName},
+        tlvs::dns_sd::{
+            dns_record::{AWDLDnsRecord, AWDLDnsRecordType},
+            DefaultArpaTLV, DefaultServiceResponseTLV,
+        },
+    };
+
+    #[test]
+    fn test_mdns_response() {
+        let service_responses = [
+            &include_bytes!("../../../test_bins/service_response_tlv_ptr.bin")[3..],
+            &include_bytes!("../../../test_bins/service_response_tlv_srv.bin")[3..],
+            &include_bytes!("../../../test_bins/service_response_tlv_txt.bin")[3..],
+        ]
+        .map(|bytes| bytes.pread::<DefaultServiceResponseTLV>(0).unwrap());
+        let arpa = include_bytes!("../../../test_bins/arpa_tlv.bin")[3..]
+            .pread::<DefaultArpaTLV>(0)
+            .unwrap();
+        let address = Ipv6Addr::new(0xfe80, 0, 0, 0, 0xbc45, 0xa1ff, 0xfed1, 0x49b6);
+
+        let mut buf = [0x00; 0x200];
+        let mut writer = MdnsResponseWriter::new(&mut buf).unwrap();
+        for service_response in service_responses.iter() {
+            writer
+                .push_service_response(service_response, 4500)
+                .unwrap();
+        }
+        writer.push_arpa(&arpa, address, 120).unwrap();
+        let length = writer.finish();
+        let message = &buf[..length];
+
+        assert_eq!(message.pread_with::<u16>(2, NETWORK).unwrap(), 0x8400);
+        assert_eq!(message.pread_with::<u16>(6, NETWORK).unwrap(), 4);
+        let mut offset = 12;
+        for service_response in service_responses.iter() {
+            let (name, name_length) = AWDLDnsName::from_wire(message, offset).unwrap();
+            assert_eq!(name, service_response.name);
+            offset += name_length;
+            let record_type = message.pread_with::<u16>(offset, NETWORK).unwrap();
+            let rdata_length = message.pread_with::<u16>(offset + 8, NETWORK).unwrap();
+            offset += 10;
+            let record =
+                AWDLDnsRecord::from_wire_rdata(message, record_type, offset, rdata_length as usize)
+                    .unwrap();
+            assert_eq!(record, service_response.record);
+            offset += rdata_length as usize;
+        }
+        let (name, name_length) = AWDLDnsName::from_wire(message, offset).unwrap();
+        assert_eq!(name, arpa.arpa);
+        offset += name_length;
+        assert_eq!(message.pread_with::<u16>(offset, NETWORK).unwrap(), 28);
+        assert_eq!(
+            message.pread_with::<u16>(offset + 2, NETWORK).unwrap(),
+            0x8001
+        );
+        assert_eq!(
+            message.pread::<[u8; 16]>(offset + 10).unwrap(),
+            address.octets()
+        );
+        assert_eq!(offset + 26, length);
+
+        // Records, which don't fit, mustn't be written partially.
+        let mut buf = [0x00; 0x20];
+        let mut writer = MdnsResponseWriter::new(&mut buf).unwrap();
+        assert!(writer
+            .push_service_response(&service_responses[1], 4500)
+            .is_err());
+        assert_eq!(writer.finish(), 12);
+
+        // The RDLENGTH has to match the RDATA.
+        let mut buf = [0x00; 0x40];
+        let mut writer = MdnsResponseWriter::new(&mut buf).unwrap();
+        assert!(writer
+            .push_record(&arpa.arpa, 28, true, 120, 16, |buf| buf
+                .pwrite([0x00u8; 15], 0))
+            .is_err());
+        assert_eq!(writer.finish(), 12);
+    }
+    #[test]
+    fn test_mdns_query() {
+        let mut message = alloc::vec![0x00; 12];
+        message[5] = 3;
+        // _airplay-p2p._tcp.local PTR
+        message.extend_from_slice(b"\x0c_airplay-p2p\x04_tcp\x05local\x00\x00\x0c\x00\x01");
+        // host.local ANY, which can't be answered through AWDL.
+        message.extend_from_slice(b"\x04host\xc0\x1e\x00\xff\x00\x01");
+        // peer._airplay-p2p._tcp.local SRV
+        message.extend_from_slice(b"\x04peer\xc0\x0c\x00\x21\x80\x01");
+
+        let query = MdnsQuery::new(&message).unwrap();
+        let mut questions = query.questions();
+        let question = questions.next().unwrap();
+        assert_eq!(question.record_type, AWDLDnsRecordType::PTR);
+        assert_eq!(
+            question.name,
+            AWDLDnsName {
+                labels: ["_airplay-p2p".into()],
+                domain: AWDLDnsCompression::TcpLocal,
+            }
+        );
+        let question = questions.next().unwrap();
+        assert_eq!(question.record_type, AWDLDnsRecordType::SRV);
+        assert_eq!(question.name.domain, AWDLDnsCompression::TcpLocal);
+        assert!(question
+            .name
+            .labels
+            .map(|label| label.0)
+            .eq([b"peer".as_slice(), b"_airplay-p2p"]));
+        assert!(questions.next().is_none());
+
+        let service_responses = [
+            &include_bytes!("../../../test_bins/service_response_tlv_ptr.bin")[3..],
+            &include_bytes!("../../../test_bins/service_response_tlv_srv.bin")[3..],
+        ]
+        .map(|bytes| bytes.pread::<DefaultServiceResponseTLV>(0).unwrap());
+        let question = query.questions().next().unwrap();
+        assert!(question.is_answered_by(&service_responses[0]));
+        assert!(!question.is_answered_by(&service_responses[1]));
+        // The names are case insensitive.
+        let mut upper_case_message = message.clone();
+        upper_case_message[13..25].make_ascii_uppercase();
+        let upper_case_query = MdnsQuery::new(&upper_case_message).unwrap();
+        let question = upper_case_query.questions().next().unwrap();
+        assert!(question.is_answered_by(&service_responses[0]));
+
+        let mut response = message.clone();
+        response[2] = 0x84;
+        assert!(MdnsQuery::new(&response).is_err());
+    }
+}
@@ -1,7 +1,13 @@
 mod arpa_tlv;
+/// Translation of AWDL service responses into mDNS responses and decoding of mDNS queries.
+pub mod mdns;
+#[cfg(feature = "heapless")]
+mod service_browser;
 // mod service_parmeters_tlv;
 mod service_response_tlv;
 
 pub use arpa_tlv::*;
+#[cfg(feature = "heapless")]
+pub use service_browser::*;
 // pub use service_parmeters_tlv::*;
 pub use service_response_tlv::*;
@@ -0,0 +1,532 @@
+use core::time::Duration;
+
+use heapless::Vec;
+use mac_parser::MACAddress;
+use scroll::{Endian, Pread, Pwrite};
+
+use crate::{
+    action_frame::AWDLActionFrame,
+    common::{AWDLDnsCompression, AWDLDnsName, AWDLStr, WireLabelIterator, MAX_WIRE_NAME_LENGTH},
+    tlvs::AWDLTLV,
+};
+
+use super::{dns_record::AWDLDnsRecord, txt_record::TxtEntries, ArpaTLV, ServiceResponseTLV};
+
+/// The maximum length of a TXT record, which can be stored by the [ServiceBrowser].
+pub const MAX_TXT_LENGTH: usize = 0x200;
+/// The type of the service parameters TLV, which isn't decoded yet.
+const SERVICE_PARAMETERS_TLV_TYPE: u8 = 0x06;
+/// The offset of the `sui` in the service parameters TLV.
+///
+/// The TLV starts with three bytes of unknown purpose, which are followed by the `sui` as a little endian [u16].
+/// After that, the encoded values follow, which aren't needed for browsing.
+const SUI_OFFSET: usize = 3;
+
+/// A name in uncompressed DNS wire format.
+type WireName = Vec<u8, MAX_WIRE_NAME_LENGTH>;
+
+/// Write the name in DNS wire format into an owned buffer.
+fn wire_name<'a, I>(name: &AWDLDnsName<I>) -> Result<WireName, scroll::Error>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    let mut buf = [0x00; MAX_WIRE_NAME_LENGTH];
+    let length = name.write_wire(&mut buf)?;
+    Ok(Vec::from_slice(&buf[..length]).unwrap_or_default())
+}
+/// Read a name, which was written by [wire_name].
+fn read_wire_name(name: &[u8]) -> Option<AWDLDnsName<WireLabelIterator<'_>>> {
+    AWDLDnsName::from_wire(name, 0).ok().map(|(name, _)| name)
+}
+/// Compare two names in DNS wire format, ignoring the case like DNS does.
+fn wire_name_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.eq_ignore_ascii_case(rhs)
+}
+
+/// The state kept for every peer.
+struct Peer {
+    address: MACAddress,
+    sui: Option<u16>,
+    hostname: Option<WireName>,
+    last_seen: Duration,
+}
+/// The records collected for a service instance.
+struct Instance {
+    peer: MACAddress,
+    name: WireName,
+    srv: Option<(u16, WireName)>,
+    txt: Vec<u8, MAX_TXT_LENGTH>,
+    last_seen: Duration,
+}
+impl Instance {
+    /// Returns the service type, which are all labels after the first one.
+    fn service_type(&self) -> &[u8] {
+        self.name
+            .first()
+            .and_then(|length| self.name.get(*length as usize + 1..))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A DNS-SD service instance, reassembled by the [ServiceBrowser].
+pub struct ServiceInstance<'a> {
+    /// The AWDL address of the peer offering the service.
+    pub peer: MACAddress,
+    /// The fullname of the instance.
+    pub name: AWDLDnsName<WireLabelIterator<'a>>,
+    /// The port and target host from the SRV record, if it was received.
+    pub srv: Option<(u16, AWDLDnsName<WireLabelIterator<'a>>)>,
+    /// The hostname of the peer from the [ArpaTLV], if it was received.
+    pub hostname: Option<AWDLDnsName<WireLabelIterator<'a>>>,
+    /// The entries of the TXT record, which are empty until it was received.
+    pub txt: TxtEntries<'a>,
+    /// The timestamp, at which a record of the instance was last received.
+    pub last_seen: Duration,
+}
+
+/// Reassembles DNS-SD service instances from the [ServiceResponseTLV]s of many frames, without allocating.
+///
+/// Up to `PEERS` peers and `INSTANCES` service instances are tracked.
+/// When a peer increments the `sui` of its service parameters, all its instances, which weren't refreshed in the same frame, are flushed.
+/// Since the service parameters precede the service responses in a frame, frames should be passed to [process_frame](Self::process_frame).
+pub struct ServiceBrowser<const PEERS: usize, const INSTANCES: usize> {
+    peers: Vec<Peer, PEERS>,
+    instances: Vec<Instance, INSTANCES>,
+}
+impl<const PEERS: usize, const INSTANCES: usize> ServiceBrowser<PEERS, INSTANCES> {
+    /// Create a new browser, which doesn't know of any services.
+    pub const fn new() -> Self {
+        Self {
+            peers: Vec::new(),
+            instances: Vec::new(),
+        }
+    }
+    /// Returns the state of the peer, inserting it, if it's unknown.
+    fn peer_mut(
+        &mut self,
+        address: MACAddress,
+        timestamp: Duration,
+    ) -> Result<&mut Peer, scroll::Error> {
+        let index = match self.peers.iter().position(|peer| peer.address == address) {
+            Some(index) => index,
+            None => {
+                self.peers
+                    .push(Peer {
+                        address,
+                        sui: None,
+                        hostname: None,
+                        last_seen: timestamp,
+                    })
+                    .map_err(|_| scroll::Error::TooBig {
+                        size: PEERS + 1,
+                        len: PEERS,
+                    })?;
+                self.peers.len() - 1
+            }
+        };
+        let peer = &mut self.peers[index];
+        peer.last_seen = timestamp;
+        Ok(peer)
+    }
+    /// Returns the instance, inserting it, if it's unknown.
+    fn instance_mut(
+        &mut self,
+        peer: MACAddress,
+        name: WireName,
+        timestamp: Duration,
+    ) -> Result<&mut Instance, scroll::Error> {
+        let index = self
+            .instances
+            .iter()
+            .position(|instance| instance.peer == peer && wire_name_eq(&instance.name, &name));
+        // The peer is only inserted, once it's certain, that the instance fits.
+        if index.is_none() && self.instances.is_full() {
+            return Err(scroll::Error::TooBig {
+                size: INSTANCES + 1,
+                len: INSTANCES,
+            });
+        }
+        self.peer_mut(peer, timestamp)?;
+        let index = match index {
+            Some(index) => index,
+            None => {
+                self.instances
+                    .push(Instance {
+                        peer,
+                        name,
+                        srv: None,
+                        txt: Vec::new(),
+                        last_seen: timestamp,
+                    })
+                    .map_err(|_| scroll::Error::TooBig {
+                        size: INSTANCES + 1,
+                        len: INSTANCES,
+                    })?;
+                self.instances.len() - 1
+            }
+        };
+        let instance = &mut self.instances[index];
+        instance.last_seen = timestamp;
+        Ok(instance)
+    }
+    /// Process all TLVs of an action frame received from the peer.
+    ///
+    /// The `sui` is updated after all other TLVs, so the instances refreshed by the frame survive a flush, regardless of the order of the TLVs.
+    pub fn process_frame<'a, I, MACIterator, LabelIterator>(
+        &mut self,
+        peer: MACAddress,
+        timestamp: Duration,
+        action_frame: &AWDLActionFrame<I>,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLTLV<'a, MACIterator, LabelIterator>> + Clone,
+        LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let mut sui = None;
+        for tlv in action_frame.tagged_data.clone() {
+            match tlv {
+                AWDLTLV::Unknown(raw_tlv) if raw_tlv.tlv_type == SERVICE_PARAMETERS_TLV_TYPE => {
+                    sui = Some(raw_tlv.slice.pread_with(SUI_OFFSET, Endian::Little)?);
+                }
+                tlv => self.process_tlv(peer, timestamp, &tlv)?,
+            }
+        }
+        if let Some(sui) = sui {
+            self.update_sui(peer, timestamp, sui)?;
+        }
+        Ok(())
+    }
+    /// Process a TLV received from the peer.
+    ///
+    /// TLVs unrelated to service discovery are ignored.
+    /// The `sui` of the service parameters is updated immediately, so the service responses of the same frame have to be processed before it.
+    pub fn process_tlv<'a, MACIterator, LabelIterator>(
+        &mut self,
+        peer: MACAddress,
+        timestamp: Duration,
+        tlv: &AWDLTLV<'a, MACIterator, LabelIterator>,
+    ) -> Result<(), scroll::Error>
+    where
+        LabelIterator: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        match tlv {
+            AWDLTLV::ServiceResponse(service_response) => {
+                self.process_service_response(peer, timestamp, service_response)
+            }
+            AWDLTLV::Arpa(arpa) => self.process_arpa(peer, timestamp, arpa),
+            AWDLTLV::Unknown(raw_tlv) if raw_tlv.tlv_type == SERVICE_PARAMETERS_TLV_TYPE => self
+                .update_sui(
+                    peer,
+                    timestamp,
+                    raw_tlv.slice.pread_with(SUI_OFFSET, Endian::Little)?,
+                )
+                .map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+    /// Process a service response received from the peer.
+    ///
+    /// The target of a PTR record with the [Null](AWDLDnsCompression::Null) compression is relative to the name of the service.
+    pub fn process_service_response<'a, I>(
+        &mut self,
+        peer: MACAddress,
+        timestamp: Duration,
+        service_response: &ServiceResponseTLV<'a, I>,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let name = wire_name(&service_response.name)?;
+        match &service_response.record {
+            AWDLDnsRecord::PTR { domain_name } => {
+                let mut instance_name = wire_name(domain_name)?;
+                if domain_name.domain == AWDLDnsCompression::Null {
+                    instance_name.pop();
+                    instance_name
+                        .extend_from_slice(&name)
+                        .map_err(|_| scroll::Error::TooBig {
+                            size: MAX_WIRE_NAME_LENGTH,
+                            len: instance_name.len() + name.len(),
+                        })?;
+                }
+                self.instance_mut(peer, instance_name, timestamp)?;
+            }
+            AWDLDnsRecord::SRV { port, target, .. } => {
+                let target = wire_name(target)?;
+                self.instance_mut(peer, name, timestamp)?.srv = Some((*port, target));
+            }
+            AWDLDnsRecord::TXT { txt_record } => {
+                let mut buf = [0x00; MAX_TXT_LENGTH];
+                let mut offset = 0;
+                for entry in txt_record.clone() {
+                    buf.gwrite(entry, &mut offset)?;
+                }
+                self.instance_mut(peer, name, timestamp)?.txt =
+                    Vec::from_slice(&buf[..offset]).unwrap_or_default();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    /// Process the [ArpaTLV] of the peer, which carries its hostname.
+    pub fn process_arpa<'a, I>(
+        &mut self,
+        peer: MACAddress,
+        timestamp: Duration,
+        arpa: &ArpaTLV<I>,
+    ) -> Result<(), scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let hostname = wire_name(&arpa.arpa)?;
+        self.peer_mut(peer, timestamp)?.hostname = Some(hostname);
+        Ok(())
+    }
+    /// Update the `sui` from the service parameters of the peer.
+    ///
+    /// If it changed, all instances of the peer, which weren't received at the timestamp, are flushed and `true` is returned.
+    pub fn update_sui(
+        &mut self,
+        peer: MACAddress,
+        timestamp: Duration,
+        sui: u16,
+    ) -> Result<bool, scroll::Error> {
+        let previous_sui = self.peer_mut(peer, timestamp)?.sui.replace(sui);
+        let flush = previous_sui.is_some_and(|previous_sui| previous_sui != sui);
+        if flush {
+            self.instances
+                .retain(|instance| instance.peer != peer || instance.last_seen >= timestamp);
+        }
+        Ok(flush)
+    }
+    /// Returns the last `sui` received from the peer.
+    pub fn sui(&self, peer: MACAddress) -> Option<u16> {
+        self.peers
+            .iter()
+            .find(|state| state.address == peer)
+            .and_then(|state| state.sui)
+    }
+    /// Remove the peer and all its instances, for example when it left the cluster.
+    pub fn remove_peer(&mut self, peer: MACAddress) {
+        self.peers.retain(|state| state.address != peer);
+        self.instances.retain(|instance| instance.peer != peer);
+    }
+    /// Remove all peers and instances, which weren't seen within the timeout.
+    pub fn expire(&mut self, now: Duration, timeout: Duration) {
+        let oldest = now.saturating_sub(timeout);
+        self.peers.retain(|peer| peer.last_seen >= oldest);
+        self.instances
+            .retain(|instance| instance.last_seen >= oldest);
+    }
+    /// Assemble the public view of the instance.
+    fn service_instance<'b>(&'b self, instance: &'b Instance) -> Option<ServiceInstance<'b>> {
+        let hostname = self
+            .peers
+            .iter()
+            .find(|peer| peer.address == instance.peer)
+            .and_then(|peer| peer.hostname.as_deref());
+        Some(ServiceInstance {
+            peer: instance.peer,
+            name: read_wire_name(&instance.name)?,
+            srv: match &instance.srv {
+                Some((port, target)) => Some((*port, read_wire_name(target)?)),
+                None => None,
+            },
+            hostname: hostname.and_then(read_wire_name),
+            txt: TxtEntries::new(&instance.txt),
+            last_seen: instance.last_seen,
+        })
+    }
+    /// Returns an iterator over all known service instances.
+    pub fn instances(&self) -> impl Iterator<Item = ServiceInstance<'_>> + Clone {
+        self.instances
+            .iter()
+            .filter_map(|instance| self.service_instance(instance))
+    }
+    /// Returns an iterator over all instances of the service type, like `_airdrop._tcp.local`.
+    pub fn browse<'a, I>(
+        &self,
+        service_type: &AWDLDnsName<I>,
+    ) -> Result<impl Iterator<Item = ServiceInstance<'_>> + Clone, scroll::Error>
+    where
+        I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+    {
+        let service_type = wire_name(service_type)?;
+        Ok(self
+            .instances
+            .iter()
+            .filter(move |instance| wire_name_eq(instance.service_type(), &service_type))
+            .filter_map(|instance| self.service_instance(instance)))
+    }
+}
+impl<const PEERS: usize, const INSTANCES: usize> Default for ServiceBrowser<PEERS, INSTANCES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_service_browser() {
+    use alloc::vec::Vec;
+
+    use crate::{
+        action_frame::AWDLActionFrameSubType,
+        common::ReadLabelIterator,
+        tlvs::{dns_sd::txt_record::TxtEntry, DefaultAWDLTLV},
+    };
+
+    let peer = MACAddress::new([0xbe, 0x45, 0xa1, 0xd1, 0x49, 0xb6]);
+    let mut browser = ServiceBrowser::<2, 4>::new();
+    for bytes in [
+        include_bytes!("../../../test_bins/service_response_tlv_ptr.bin").as_slice(),
+        include_bytes!("../../../test_bins/service_response_tlv_srv.bin"),
+        include_bytes!("../../../test_bins/service_response_tlv_txt.bin"),
+        include_bytes!("../../../test_bins/arpa_tlv.bin"),
+        include_bytes!("../../../test_bins/service_parameters_tlv.bin"),
+    ] {
+        let tlv = bytes.pread::<DefaultAWDLTLV>(0).unwrap();
+        browser
+            .process_tlv(peer, Duration::from_secs(1), &tlv)
+            .unwrap();
+    }
+
+    let airplay = browser
+        .browse(&AWDLDnsName {
+            labels: ["_airplay-p2p".into()],
+            domain: AWDLDnsCompression::TcpLocal,
+        })
+        .unwrap()
+        .collect::<Vec<_>>();
+    // The PTR and SRV records are merged, even though the case of the instance names differs.
+    assert_eq!(airplay.len(), 1);
+    assert_eq!(airplay[0].peer, peer);
+    let (port, target) = airplay[0].srv.unwrap();
+    assert_eq!(port, 7000);
+    assert_eq!(
+        target,
+        AWDLDnsName {
+            labels: ["dcc83dc2-fae7-4043-8c7a-a8b6bf49eaad".into()],
+            domain: AWDLDnsCompression::Local,
+        }
+    );
+    assert_eq!(
+        airplay[0].hostname.unwrap(),
+        AWDLDnsName {
+            labels: ["simon-framework".into()],
+            domain: AWDLDnsCompression::Local,
+        }
+    );
+
+    let airdrop = browser
+        .browse(&AWDLDnsName::<[AWDLStr; 0]> {
+            labels: [],
+            domain: AWDLDnsCompression::AirDropTcpLocal,
+        })
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(airdrop.len(), 1);
+    assert_eq!(airdrop[0].srv, None);
+    assert_eq!(
+        airdrop[0].txt.get("flags"),
+        Some(TxtEntry::new("flags", b"999"))
+    );
+
+    assert_eq!(browser.sui(peer), Some(55));
+
+    // A new sui flushes all records, which weren't refreshed in the same frame.
+    assert!(!browser
+        .update_sui(peer, Duration::from_secs(2), 55)
+        .unwrap());
+    let srv = include_bytes!("../../../test_bins/service_response_tlv_srv.bin")[3..]
+        .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+        .unwrap();
+    browser
+        .process_service_response(peer, Duration::from_secs(3), &srv)
+        .unwrap();
+    assert!(browser
+        .update_sui(peer, Duration::from_secs(3), 56)
+        .unwrap());
+    assert_eq!(browser.instances().count(), 1);
+
+    browser.expire(Duration::from_secs(10), Duration::from_secs(5));
+    assert_eq!(browser.instances().count(), 0);
+
+    let other_peer = MACAddress::new([0x02; 6]);
+    let third_peer = MACAddress::new([0x03; 6]);
+    browser
+        .process_service_response(other_peer, Duration::from_secs(11), &srv)
+        .unwrap();
+    browser
+        .process_service_response(peer, Duration::from_secs(11), &srv)
+        .unwrap();
+    assert!(browser
+        .process_service_response(third_peer, Duration::from_secs(11), &srv)
+        .is_err());
+    browser.remove_peer(other_peer);
+    assert!(browser.instances().all(|instance| instance.peer == peer));
+
+    // Frames carry the service parameters before the service responses.
+    let mut browser = ServiceBrowser::<2, 4>::new();
+    let tlv = |bytes: &'static [u8]| bytes.pread::<DefaultAWDLTLV>(0).unwrap();
+    let service_parameters =
+        include_bytes!("../../../test_bins/service_parameters_tlv.bin").as_slice();
+    let mut next_service_parameters = service_parameters.to_vec();
+    next_service_parameters[3 + SUI_OFFSET] += 1;
+    fn frame<I>(tagged_data: I) -> AWDLActionFrame<I> {
+        AWDLActionFrame {
+            subtype: AWDLActionFrameSubType::MIF,
+            phy_tx_time: Duration::ZERO,
+            target_tx_time: Duration::ZERO,
+            tagged_data,
+        }
+    }
+    browser
+        .process_frame(
+            peer,
+            Duration::from_secs(1),
+            &frame([
+                tlv(include_bytes!("../../../test_bins/arpa_tlv.bin")),
+                tlv(service_parameters),
+                tlv(include_bytes!(
+                    "../../../test_bins/service_response_tlv_ptr.bin"
+                )),
+                tlv(include_bytes!(
+                    "../../../test_bins/service_response_tlv_srv.bin"
+                )),
+                tlv(include_bytes!(
+                    "../../../test_bins/service_response_tlv_txt.bin"
+                )),
+            ]),
+        )
+        .unwrap();
+    assert_eq!(browser.instances().count(), 2);
+    // The new sui only flushes the AirDrop instance, which isn't part of the frame.
+    browser
+        .process_frame(
+            peer,
+            Duration::from_secs(2),
+            &frame([
+                next_service_parameters.pread::<DefaultAWDLTLV>(0).unwrap(),
+                tlv(include_bytes!(
+                    "../../../test_bins/service_response_tlv_ptr.bin"
+                )),
+            ]),
+        )
+        .unwrap();
+    assert_eq!(browser.sui(peer), Some(56));
+    let instances = browser.instances().collect::<Vec<_>>();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].srv.unwrap().0, 7000);
+    assert!(instances[0].hostname.is_some());
+
+    // A peer, whose instance doesn't fit, mustn't be inserted.
+    let mut browser = ServiceBrowser::<2, 1>::new();
+    browser
+        .process_service_response(peer, Duration::from_secs(1), &srv)
+        .unwrap();
+    assert!(browser
+        .process_service_response(other_peer, Duration::from_secs(1), &srv)
+        .is_err());
+    assert!(browser.peers.iter().all(|state| state.address == peer));
+}
@@ -1,22 +1,103 @@
+use core::{
+    hash::{Hash, Hasher},
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
 use macro_bits::serializable_enum;
 use scroll::{
     ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
-    Pread, Pwrite, NETWORK,
+    Endian, Pread, Pwrite, NETWORK,
 };
 
-use crate::common::{AWDLDnsName, AWDLStr, ReadLabelIterator};
+use crate::common::{AWDLDnsName, AWDLStr, ReadLabelIterator, WireLabelIterator};
+
+use super::txt_record::TxtEntries;
 
 serializable_enum! {
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
     pub enum AWDLDnsRecordType: u8 {
+        A => 0x1,
         #[default]
         PTR => 0xc,
         TXT => 0x10,
-        SRV => 0x21
+        AAAA => 0x1c,
+        SRV => 0x21,
+        NSEC => 0x2f
     }
 }
 
-#[derive(Clone, Copy, Debug, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The type bitmaps of an NSEC record, as specified in RFC 4034.
+pub struct NsecTypeBitmaps<'a>(pub &'a [u8]);
+impl NsecTypeBitmaps<'_> {
+    /// Returns an iterator over the record types present in the bitmaps.
+    ///
+    /// Iteration stops at the first malformed window.
+    pub fn types(&self) -> impl Iterator<Item = u16> + Clone + '_ {
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            let window = self.0.gread::<u8>(&mut offset).ok()? as u16;
+            let length = self.0.gread::<u8>(&mut offset).ok()? as usize;
+            let bitmap = self.0.gread_with::<&[u8]>(&mut offset, length).ok()?;
+            Some((window, bitmap))
+        })
+        .flat_map(|(window, bitmap)| {
+            bitmap
+                .iter()
+                .enumerate()
+                .flat_map(move |(byte_index, byte)| {
+                    (0..8)
+                        .filter(move |bit| byte & (0x80 >> bit) != 0)
+                        .map(move |bit| window << 8 | (byte_index as u16) << 3 | bit)
+                })
+        })
+    }
+    /// Check if the record type is present in the bitmaps.
+    pub fn contains(&self, record_type: u16) -> bool {
+        self.types().any(|present| present == record_type)
+    }
+    /// Encode the record types into the buffer.
+    ///
+    /// Types in different windows, which are 256 types wide, have to be in ascending order.
+    ///
+    /// Returns the number of bytes written.
+    pub fn encode(
+        record_types: impl IntoIterator<Item = u16>,
+        buf: &mut [u8],
+    ) -> Result<usize, scroll::Error> {
+        let mut offset = 0;
+        // The window currently being written and the offset of its length byte.
+        let mut current_window: Option<(u8, usize)> = None;
+        for record_type in record_types {
+            let [window, low] = record_type.to_be_bytes();
+            let byte_index = (low >> 3) as usize;
+            let length_offset = match current_window {
+                Some((current, length_offset)) if current == window => length_offset,
+                Some((current, _)) if current > window => {
+                    return Err(scroll::Error::BadInput {
+                        size: offset,
+                        msg: "NSEC type windows aren't sorted.",
+                    })
+                }
+                _ => {
+                    buf.gwrite(window, &mut offset)?;
+                    buf.gwrite(0u8, &mut offset)?;
+                    current_window = Some((window, offset - 1));
+                    offset - 1
+                }
+            };
+            // The bitmap is extended with zeroes, up to the byte containing the type.
+            while buf[length_offset] as usize <= byte_index {
+                buf.gwrite(0u8, &mut offset)?;
+                buf[length_offset] += 1;
+            }
+            buf[length_offset + 1 + byte_index] |= 0x80 >> (low & 0x7);
+        }
+        Ok(offset)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 /// A DNS record as encoded by AWDL.
 pub enum AWDLDnsRecord<'a, I> {
     /// Pointer
@@ -34,6 +115,19 @@ pub enum AWDLDnsRecord<'a, I> {
         port: u16,
         target: AWDLDnsName<I>,
     },
+    /// IPv4 address
+    A {
+        address: Ipv4Addr,
+    },
+    /// IPv6 address
+    AAAA {
+        address: Ipv6Addr,
+    },
+    /// Next secure
+    NSEC {
+        next_domain_name: AWDLDnsName<I>,
+        type_bitmaps: NsecTypeBitmaps<'a>,
+    },
     UnknownRecord {
         record_type: u8,
         body: &'a [u8],
@@ -50,6 +144,9 @@ where
             AWDLDnsRecord::PTR { .. } => AWDLDnsRecordType::PTR,
             AWDLDnsRecord::TXT { .. } => AWDLDnsRecordType::TXT,
             AWDLDnsRecord::SRV { .. } => AWDLDnsRecordType::SRV,
+            AWDLDnsRecord::A { .. } => AWDLDnsRecordType::A,
+            AWDLDnsRecord::AAAA { .. } => AWDLDnsRecordType::AAAA,
+            AWDLDnsRecord::NSEC { .. } => AWDLDnsRecordType::NSEC,
             AWDLDnsRecord::UnknownRecord { record_type, .. } => {
                 AWDLDnsRecordType::Unknown(*record_type)
             }
@@ -98,6 +195,22 @@ where
                     && lhs_port == rhs_port
                     && lhs_target == rhs_target
             }
+            (AWDLDnsRecord::A { address: lhs }, AWDLDnsRecord::A { address: rhs }) => lhs == rhs,
+            (AWDLDnsRecord::AAAA { address: lhs }, AWDLDnsRecord::AAAA { address: rhs }) => {
+                lhs == rhs
+            }
+            (
+                AWDLDnsRecord::NSEC {
+                    next_domain_name: lhs_next_domain_name,
+                    type_bitmaps: lhs_type_bitmaps,
+                },
+                AWDLDnsRecord::NSEC {
+                    next_domain_name: rhs_next_domain_name,
+                    type_bitmaps: rhs_type_bitmaps,
+                },
+            ) => {
+                lhs_next_domain_name == rhs_next_domain_name && lhs_type_bitmaps == rhs_type_bitmaps
+            }
             (
                 AWDLDnsRecord::UnknownRecord {
                     record_type: lhs_record_type,
@@ -112,6 +225,40 @@ where
         }
     }
 }
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for AWDLDnsRecord<'a, I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.record_type().hash(state);
+        match self {
+            AWDLDnsRecord::PTR { domain_name } => domain_name.hash(state),
+            AWDLDnsRecord::TXT { txt_record } => {
+                for entry in txt_record.clone() {
+                    entry.hash(state);
+                }
+            }
+            AWDLDnsRecord::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                priority.hash(state);
+                weight.hash(state);
+                port.hash(state);
+                target.hash(state);
+            }
+            AWDLDnsRecord::A { address } => address.hash(state),
+            AWDLDnsRecord::AAAA { address } => address.hash(state),
+            AWDLDnsRecord::NSEC {
+                next_domain_name,
+                type_bitmaps,
+            } => {
+                next_domain_name.hash(state);
+                type_bitmaps.hash(state);
+            }
+            AWDLDnsRecord::UnknownRecord { body, .. } => body.hash(state),
+        }
+    }
+}
 impl<'a, I> MeasureWith<()> for AWDLDnsRecord<'a, I>
 where
     I: IntoIterator<Item = AWDLStr<'a>> + Clone,
@@ -125,8 +272,14 @@ where
                 .map(|x| x.size_in_bytes())
                 .sum(),
             AWDLDnsRecord::SRV { target, .. } => target.measure_with(ctx) + 6,
+            AWDLDnsRecord::A { .. } => 4,
+            AWDLDnsRecord::AAAA { .. } => 16,
+            AWDLDnsRecord::NSEC {
+                next_domain_name,
+                type_bitmaps,
+            } => next_domain_name.measure_with(ctx) + type_bitmaps.0.len(),
             AWDLDnsRecord::UnknownRecord { body, .. } => body.len(),
-        }) + 1
+        }) + 5
     }
 }
 impl<'a> TryFromCtx<'a> for AWDLDnsRecord<'a, ReadLabelIterator<'a>> {
@@ -134,28 +287,73 @@ impl<'a> TryFromCtx<'a> for AWDLDnsRecord<'a, ReadLabelIterator<'a>> {
     fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
         let mut offset = 0;
         let record_type = AWDLDnsRecordType::from_bits(from.gread(&mut offset)?);
-        offset += 4; // Skip length and unknown, because it's irrelevant for us.
-        Ok((
-            match record_type {
-                AWDLDnsRecordType::PTR => Self::PTR {
-                    domain_name: from.gread(&mut offset)?,
-                },
-                AWDLDnsRecordType::TXT => Self::TXT {
-                    txt_record: ReadLabelIterator::new(&from[offset..]),
-                },
-                AWDLDnsRecordType::SRV => Self::SRV {
-                    priority: from.gread_with(&mut offset, NETWORK)?,
-                    weight: from.gread_with(&mut offset, NETWORK)?,
-                    port: from.gread_with(&mut offset, NETWORK)?,
-                    target: from.gread(&mut offset)?,
-                },
-                AWDLDnsRecordType::Unknown(record_type) => Self::UnknownRecord {
-                    record_type,
-                    body: &from[offset..],
-                },
+        let length = from.gread_with::<u16>(&mut offset, Endian::Little)? as usize;
+        offset += 2; // Skip unknown, because it's irrelevant for us.
+        let body = from.gread_with::<&'a [u8]>(&mut offset, length)?;
+
+        let mut body_offset = 0;
+        let record = match record_type {
+            AWDLDnsRecordType::PTR => Self::PTR {
+                domain_name: body.gread(&mut body_offset)?,
+            },
+            AWDLDnsRecordType::TXT => {
+                body_offset = body.len();
+                Self::TXT {
+                    txt_record: ReadLabelIterator::new(body),
+                }
+            }
+            AWDLDnsRecordType::SRV => Self::SRV {
+                priority: body.gread_with(&mut body_offset, NETWORK)?,
+                weight: body.gread_with(&mut body_offset, NETWORK)?,
+                port: body.gread_with(&mut body_offset, NETWORK)?,
+                target: body.gread(&mut body_offset)?,
+            },
+            AWDLDnsRecordType::A => Self::A {
+                address: Ipv4Addr::from(body.gread::<[u8; 4]>(&mut body_offset)?),
             },
-            offset,
-        ))
+            AWDLDnsRecordType::AAAA => Self::AAAA {
+                address: Ipv6Addr::from(body.gread::<[u8; 16]>(&mut body_offset)?),
+            },
+            AWDLDnsRecordType::NSEC => {
+                // The labels of the name are followed by the two byte compression.
+                let mut name_length = 0;
+                while body.pread::<u8>(name_length)? < 0xc0 {
+                    name_length += 1 + body.pread::<u8>(name_length)? as usize;
+                }
+                name_length += 2;
+                let next_domain_name = body
+                    .gread_with::<&'a [u8]>(&mut body_offset, name_length)?
+                    .pread(0)?;
+                let type_bitmaps = NsecTypeBitmaps(&body[body_offset..]);
+                body_offset = body.len();
+                Self::NSEC {
+                    next_domain_name,
+                    type_bitmaps,
+                }
+            }
+            AWDLDnsRecordType::Unknown(record_type) => {
+                body_offset = body.len();
+                Self::UnknownRecord { record_type, body }
+            }
+        };
+        if body_offset != body.len() {
+            return Err(scroll::Error::BadInput {
+                size: offset,
+                msg: "DNS record length doesn't match its body.",
+            });
+        }
+        Ok((record, offset))
+    }
+}
+impl<'a> AWDLDnsRecord<'a, ReadLabelIterator<'a>> {
+    /// Returns the key/value [entries](super::txt_record::TxtEntry) of a TXT record.
+    pub fn txt_entries(&self) -> Option<TxtEntries<'a>> {
+        match self {
+            AWDLDnsRecord::TXT { txt_record } => {
+                Some(TxtEntries::new(txt_record.remaining_bytes()))
+            }
+            _ => None,
+        }
     }
 }
 impl<'a, I> TryIntoCtx for AWDLDnsRecord<'a, I>
@@ -188,11 +386,293 @@ where
                 buf.gwrite_with(port, &mut offset, NETWORK)?;
                 buf.gwrite(target, &mut offset)?;
             }
+            AWDLDnsRecord::A { address } => {
+                buf.gwrite(address.octets(), &mut offset)?;
+            }
+            AWDLDnsRecord::AAAA { address } => {
+                buf.gwrite(address.octets(), &mut offset)?;
+            }
+            AWDLDnsRecord::NSEC {
+                next_domain_name,
+                type_bitmaps,
+            } => {
+                buf.gwrite(next_domain_name, &mut offset)?;
+                buf.gwrite(type_bitmaps.0, &mut offset)?;
+            }
             AWDLDnsRecord::UnknownRecord { body, .. } => {
                 buf.gwrite(body, &mut offset)?;
             }
         };
-        buf.pwrite(offset as u16 - 5, 1)?; // Length
+        buf.pwrite_with(offset as u16 - 5, 1, Endian::Little)?; // Length
         Ok(offset)
     }
 }
+impl<'a, I> AWDLDnsRecord<'a, I>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    /// Returns the length of the RDATA in DNS wire format.
+    pub fn wire_rdata_length(&self) -> usize {
+        match self {
+            AWDLDnsRecord::PTR { domain_name } => domain_name.wire_length(),
+            AWDLDnsRecord::TXT { txt_record } => txt_record
+                .clone()
+                .into_iter()
+                .map(|x| x.size_in_bytes())
+                .sum(),
+            AWDLDnsRecord::SRV { target, .. } => target.wire_length() + 6,
+            AWDLDnsRecord::A { .. } => 4,
+            AWDLDnsRecord::AAAA { .. } => 16,
+            AWDLDnsRecord::NSEC {
+                next_domain_name,
+                type_bitmaps,
+            } => next_domain_name.wire_length() + type_bitmaps.0.len(),
+            AWDLDnsRecord::UnknownRecord { body, .. } => body.len(),
+        }
+    }
+    /// Write the RDATA in DNS wire format, with all names expanded.
+    ///
+    /// The DNS record type is the same as the [AWDL record type](AWDLDnsRecordType).
+    pub fn write_wire_rdata(&self, buf: &mut [u8]) -> Result<usize, scroll::Error> {
+        let mut offset = 0;
+        match self {
+            AWDLDnsRecord::PTR { domain_name } => {
+                offset += domain_name.write_wire(buf)?;
+            }
+            AWDLDnsRecord::TXT { txt_record } => {
+                for record in txt_record.clone() {
+                    buf.gwrite(record, &mut offset)?;
+                }
+            }
+            AWDLDnsRecord::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                buf.gwrite_with(*priority, &mut offset, NETWORK)?;
+                buf.gwrite_with(*weight, &mut offset, NETWORK)?;
+                buf.gwrite_with(*port, &mut offset, NETWORK)?;
+                offset += target.write_wire(&mut buf[offset..])?;
+            }
+            AWDLDnsRecord::A { address } => {
+                buf.gwrite(address.octets(), &mut offset)?;
+            }
+            AWDLDnsRecord::AAAA { address } => {
+                buf.gwrite(address.octets(), &mut offset)?;
+            }
+            AWDLDnsRecord::NSEC {
+                next_domain_name,
+                type_bitmaps,
+            } => {
+                offset += next_domain_name.write_wire(buf)?;
+                buf.gwrite(type_bitmaps.0, &mut offset)?;
+            }
+            AWDLDnsRecord::UnknownRecord { body, .. } => {
+                buf.gwrite(*body, &mut offset)?;
+            }
+        }
+        Ok(offset)
+    }
+}
+impl<'a> AWDLDnsRecord<'a, WireLabelIterator<'a>> {
+    /// Read the RDATA of a record in DNS wire format from the message.
+    ///
+    /// The RDATA is located at the offset and spans the length, while names in it may point into the rest of the message.
+    pub fn from_wire_rdata(
+        message: &'a [u8],
+        record_type: u16,
+        offset: usize,
+        length: usize,
+    ) -> Result<Self, scroll::Error> {
+        let rdata = message.pread_with::<&[u8]>(offset, length)?;
+        let Ok(record_type) = u8::try_from(record_type) else {
+            return Err(scroll::Error::BadInput {
+                size: offset,
+                msg: "DNS record type can't be represented in AWDL.",
+            });
+        };
+        // Names may point outside of the RDATA, but they have to end within it.
+        let read_name = |name_offset: usize| {
+            let (name, name_length) = AWDLDnsName::from_wire(message, name_offset)?;
+            if name_offset + name_length > offset + length {
+                return Err(scroll::Error::BadInput {
+                    size: name_offset,
+                    msg: "DNS name exceeds the RDATA.",
+                });
+            }
+            Ok((name, name_length))
+        };
+        Ok(match AWDLDnsRecordType::from_bits(record_type) {
+            AWDLDnsRecordType::PTR => Self::PTR {
+                domain_name: read_name(offset)?.0,
+            },
+            AWDLDnsRecordType::TXT => Self::TXT {
+                txt_record: WireLabelIterator::character_strings(rdata)?,
+            },
+            AWDLDnsRecordType::SRV => Self::SRV {
+                priority: rdata.pread_with(0, NETWORK)?,
+                weight: rdata.pread_with(2, NETWORK)?,
+                port: rdata.pread_with(4, NETWORK)?,
+                target: read_name(offset + 6)?.0,
+            },
+            AWDLDnsRecordType::A => Self::A {
+                address: Ipv4Addr::from(rdata.pread::<[u8; 4]>(0)?),
+            },
+            AWDLDnsRecordType::AAAA => Self::AAAA {
+                address: Ipv6Addr::from(rdata.pread::<[u8; 16]>(0)?),
+            },
+            AWDLDnsRecordType::NSEC => {
+                let (next_domain_name, name_length) = read_name(offset)?;
+                Self::NSEC {
+                    next_domain_name,
+                    type_bitmaps: NsecTypeBitmaps(&rdata[name_length..]),
+                }
+            }
+            AWDLDnsRecordType::Unknown(record_type) => Self::UnknownRecord {
+                record_type,
+                body: rdata,
+            },
+        })
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_dns_record_wire() {
+    use crate::common::AWDLDnsCompression;
+
+    let srv: AWDLDnsRecord<[AWDLStr; 1]> = AWDLDnsRecord::SRV {
+        priority: 0,
+        weight: 0,
+        port: 7000,
+        target: AWDLDnsName {
+            labels: ["dcc83dc2-fae7-4043-8c7a-a8b6bf49eaad".into()],
+            domain: AWDLDnsCompression::Local,
+        },
+    };
+    let mut buf = [0x00; 0x40];
+    let length = srv.write_wire_rdata(&mut buf).unwrap();
+    assert_eq!(length, srv.wire_rdata_length());
+    assert_eq!(&buf[..6], [0x00, 0x00, 0x00, 0x00, 0x1b, 0x58]);
+    assert_eq!(&buf[length - 7..length], b"\x05local\x00");
+    assert_eq!(
+        AWDLDnsRecord::from_wire_rdata(&buf, AWDLDnsRecordType::SRV.into_bits() as u16, 0, length)
+            .unwrap(),
+        srv
+    );
+    // The target has to end within the RDATA.
+    assert!(AWDLDnsRecord::from_wire_rdata(
+        &buf,
+        AWDLDnsRecordType::SRV.into_bits() as u16,
+        0,
+        length - 1
+    )
+    .is_err());
+    assert!(AWDLDnsRecord::from_wire_rdata(
+        b"\x04peer\x00",
+        AWDLDnsRecordType::PTR.into_bits() as u16,
+        0,
+        3
+    )
+    .is_err());
+
+    let txt: AWDLDnsRecord<[AWDLStr; 2]> = AWDLDnsRecord::TXT {
+        txt_record: ["flags=999".into(), "".into()],
+    };
+    let length = txt.write_wire_rdata(&mut buf).unwrap();
+    assert_eq!(&buf[..length], b"\x09flags=999\x00");
+    assert_eq!(
+        AWDLDnsRecord::from_wire_rdata(&buf, 16, 0, length).unwrap(),
+        txt
+    );
+
+    assert!(AWDLDnsRecord::from_wire_rdata(&buf, 0x100, 0, length).is_err());
+}
+#[cfg(test)]
+#[test]
+fn test_address_and_nsec_records() {
+    use alloc::vec;
+
+    use crate::common::AWDLDnsCompression;
+
+    let mut type_bitmaps = [0x00; 0x20];
+    let length = NsecTypeBitmaps::encode([1, 28, 33, 47, 257], &mut type_bitmaps).unwrap();
+    assert_eq!(
+        &type_bitmaps[..length],
+        [0x00, 0x06, 0x40, 0x00, 0x00, 0x08, 0x40, 0x01, 0x01, 0x01, 0x40]
+    );
+    let type_bitmaps = NsecTypeBitmaps(&type_bitmaps[..length]);
+    assert!(type_bitmaps.types().eq([1, 28, 33, 47, 257]));
+    assert!(type_bitmaps.contains(28));
+    assert!(!type_bitmaps.contains(16));
+    assert!(NsecTypeBitmaps::encode([257, 1], &mut [0x00; 0x20]).is_err());
+
+    let records: [AWDLDnsRecord<[AWDLStr; 1]>; 3] = [
+        AWDLDnsRecord::A {
+            address: Ipv4Addr::new(169, 254, 1, 2),
+        },
+        AWDLDnsRecord::AAAA {
+            address: Ipv6Addr::new(0xfe80, 0, 0, 0, 0xbc45, 0xa1ff, 0xfed1, 0x49b6),
+        },
+        AWDLDnsRecord::NSEC {
+            next_domain_name: AWDLDnsName {
+                labels: ["simon-framework".into()],
+                domain: AWDLDnsCompression::Local,
+            },
+            type_bitmaps,
+        },
+    ];
+    for record in records {
+        let mut buf = vec![0x00; record.measure_with(&())];
+        assert_eq!(buf.pwrite(record, 0).unwrap(), buf.len());
+        assert_eq!(
+            buf.pread::<AWDLDnsRecord<ReadLabelIterator>>(0).unwrap(),
+            record
+        );
+
+        let mut wire = vec![0x00; record.wire_rdata_length()];
+        record.write_wire_rdata(&mut wire).unwrap();
+        assert_eq!(
+            AWDLDnsRecord::from_wire_rdata(
+                &wire,
+                record.record_type().into_bits() as u16,
+                0,
+                wire.len()
+            )
+            .unwrap(),
+            record
+        );
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_dns_record_lengths() {
+    // The length of an A record has to be four.
+    assert!(b"\x01\x05\x00\x00\x00\xa9\xfe\x01\x02\x03"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .is_err());
+    assert!(b"\x01\x03\x00\x00\x00\xa9\xfe\x01"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .is_err());
+    // The length exceeds the bytes.
+    assert!(b"\x10\x0a\x00\x00\x00\x09flags=9"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .is_err());
+    // The body of a PTR record is too short for a name.
+    assert!(b"\x0c\x01\x00\x00\x00\xc0"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .is_err());
+    assert!(b"\x2f\x00\x00\x00\x00"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .is_err());
+
+    let mut offset = 0;
+    let record = b"\x01\x04\x00\x00\x00\xa9\xfe\x01\x02\xff"
+        .gread::<AWDLDnsRecord<ReadLabelIterator>>(&mut offset)
+        .unwrap();
+    assert_eq!(offset, 9);
+    let expected: AWDLDnsRecord<[AWDLStr; 0]> = AWDLDnsRecord::A {
+        address: Ipv4Addr::new(169, 254, 1, 2),
+    };
+    assert_eq!(record, expected);
+}
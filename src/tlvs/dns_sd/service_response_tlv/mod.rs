@@ -1,4 +1,10 @@
 pub mod dns_record;
+mod service_description;
+pub mod txt_record;
+
+pub use service_description::ServiceDescription;
+
+use core::hash::{Hash, Hasher};
 
 use crate::{
     common::{AWDLDnsName, AWDLStr, ReadLabelIterator},
@@ -12,7 +18,7 @@ use scroll::{
     Endian, Pread, Pwrite,
 };
 
-#[derive(Clone, Copy, Debug, Hash)]
+#[derive(Clone, Copy, Debug)]
 /// This TLV contains data about services offered by the peer.
 pub struct ServiceResponseTLV<'a, I> {
     /// The fullname of the service.
@@ -35,12 +41,18 @@ where
         self.name == other.name && self.record == other.record
     }
 }
+impl<'a, I: IntoIterator<Item = AWDLStr<'a>> + Clone> Hash for ServiceResponseTLV<'a, I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.record.hash(state);
+    }
+}
 impl<'a, I> MeasureWith<()> for ServiceResponseTLV<'a, I>
 where
     I: IntoIterator<Item = AWDLStr<'a>> + Clone,
 {
     fn measure_with(&self, ctx: &()) -> usize {
-        6 + self.name.measure_with(ctx) + self.record.measure_with(ctx)
+        2 + self.name.measure_with(ctx) + self.record.measure_with(ctx)
     }
 }
 impl<'a> TryFromCtx<'a> for ServiceResponseTLV<'a, ReadLabelIterator<'a>> {
@@ -49,8 +61,15 @@ impl<'a> TryFromCtx<'a> for ServiceResponseTLV<'a, ReadLabelIterator<'a>> {
         let mut offset = 0;
 
         let length = from.gread_with::<u16>(&mut offset, Endian::Little)? as usize;
+        // The length also covers the record type, which follows the name.
+        let Some(name_length) = length.checked_sub(1) else {
+            return Err(scroll::Error::BadInput {
+                size: offset,
+                msg: "Service response name length can't be zero.",
+            });
+        };
         let name = from
-            .gread_with::<&'a [u8]>(&mut offset, length - 1)?
+            .gread_with::<&'a [u8]>(&mut offset, name_length)?
             .pread(0)?;
         let record = from.gread(&mut offset)?;
         Ok((Self { name, record }, offset))
@@ -74,17 +93,112 @@ where
     }
 }
 
+/// A builder for the [ServiceResponseTLV].
+pub struct ServiceResponseTLVBuilder<'a, I> {
+    name: Option<AWDLDnsName<I>>,
+    record: Option<AWDLDnsRecord<'a, I>>,
+}
+impl<'a, I> ServiceResponseTLVBuilder<'a, I>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    /// Create a new builder without a name and record.
+    pub const fn new() -> Self {
+        Self {
+            name: None,
+            record: None,
+        }
+    }
+    /// Set the fullname of the service.
+    pub fn name(mut self, name: AWDLDnsName<I>) -> Self {
+        self.name = Some(name);
+        self
+    }
+    /// Set the DNS record.
+    pub fn record(mut self, record: AWDLDnsRecord<'a, I>) -> Self {
+        self.record = Some(record);
+        self
+    }
+    /// Set a PTR record.
+    pub fn ptr(self, domain_name: AWDLDnsName<I>) -> Self {
+        self.record(AWDLDnsRecord::PTR { domain_name })
+    }
+    /// Set a TXT record.
+    pub fn txt(self, txt_record: I) -> Self {
+        self.record(AWDLDnsRecord::TXT { txt_record })
+    }
+    /// Set a SRV record.
+    ///
+    /// Like macOS, this uses a priority and weight of zero.
+    pub fn srv(self, port: u16, target: AWDLDnsName<I>) -> Self {
+        self.record(AWDLDnsRecord::SRV {
+            priority: 0,
+            weight: 0,
+            port,
+            target,
+        })
+    }
+    /// Check that all labels are valid DNS labels.
+    fn validate_labels(labels: I) -> Result<(), scroll::Error> {
+        if labels
+            .into_iter()
+            .any(|label| label.is_empty() || label.len() > 63)
+        {
+            Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "DNS labels must be between 1 and 63 bytes long.",
+            })
+        } else {
+            Ok(())
+        }
+    }
+    /// Validate the name and record and return the TLV.
+    pub fn build(self) -> Result<ServiceResponseTLV<'a, I>, scroll::Error> {
+        let (Some(name), Some(record)) = (self.name, self.record) else {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Name and record are required.",
+            });
+        };
+        Self::validate_labels(name.labels.clone())?;
+        match &record {
+            AWDLDnsRecord::PTR { domain_name } => {
+                Self::validate_labels(domain_name.labels.clone())?
+            }
+            AWDLDnsRecord::SRV { target, .. } => Self::validate_labels(target.labels.clone())?,
+            _ => {}
+        }
+        let tlv = ServiceResponseTLV { name, record };
+        if tlv.measure_with(&()) > u16::MAX as usize {
+            return Err(scroll::Error::TooBig {
+                size: tlv.measure_with(&()),
+                len: u16::MAX as usize,
+            });
+        }
+        Ok(tlv)
+    }
+}
+impl<'a, I> Default for ServiceResponseTLVBuilder<'a, I>
+where
+    I: IntoIterator<Item = AWDLStr<'a>> + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// The default service response tlv returned by reading.
 pub type DefaultServiceResponseTLV<'a> = ServiceResponseTLV<'a, ReadLabelIterator<'a>>;
 
 #[cfg(test)]
+#[allow(clippy::needless_update)]
 mod service_response_tests {
     use alloc::vec;
     use scroll::{ctx::MeasureWith, Pread, Pwrite};
 
     use crate::{
         common::{AWDLDnsCompression, AWDLDnsName, ReadLabelIterator},
-        tlvs::dns_sd::{dns_record::AWDLDnsRecord, ServiceResponseTLV},
+        tlvs::dns_sd::{dns_record::AWDLDnsRecord, ServiceResponseTLV, ServiceResponseTLVBuilder},
     };
 
     #[test]
@@ -173,4 +287,89 @@ mod service_response_tests {
         buf.as_mut_slice().pwrite(service_response_tlv, 0).unwrap();
         assert_eq!(buf, bytes);
     }
+    #[test]
+    fn test_service_response_tlv_builder() {
+        let bytes = &include_bytes!("../../../../test_bins/service_response_tlv_srv.bin")[3..];
+
+        let service_response_tlv = ServiceResponseTLVBuilder::new()
+            .name(AWDLDnsName {
+                labels: vec!["34fd6a0c9a42@1.021".into(), "_airplay-p2p".into()],
+                domain: AWDLDnsCompression::TcpLocal,
+            })
+            .srv(
+                7000,
+                AWDLDnsName {
+                    labels: vec!["dcc83dc2-fae7-4043-8c7a-a8b6bf49eaad".into()],
+                    domain: AWDLDnsCompression::Local,
+                },
+            )
+            .build()
+            .unwrap();
+        let mut buf = vec![0x00; service_response_tlv.measure_with(&())];
+        buf.as_mut_slice().pwrite(service_response_tlv, 0).unwrap();
+        assert_eq!(buf, bytes);
+
+        assert!(ServiceResponseTLVBuilder::new()
+            .txt(vec!["flags=999".into()])
+            .build()
+            .is_err());
+        assert!(ServiceResponseTLVBuilder::new()
+            .name(AWDLDnsName {
+                labels: vec!["".into()],
+                domain: AWDLDnsCompression::Local,
+            })
+            .txt(vec![])
+            .build()
+            .is_err());
+    }
+    #[test]
+    fn test_service_response_tlv_lengths() {
+        let bytes = &include_bytes!("../../../../test_bins/service_response_tlv_srv.bin")[3..];
+
+        // A name length of zero used to underflow.
+        let mut zero_length = bytes.to_vec();
+        zero_length[..2].copy_from_slice(&[0x00, 0x00]);
+        assert!(zero_length
+            .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+            .is_err());
+        assert!([0x00, 0x00]
+            .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+            .is_err());
+        // The name is too short to contain the compression.
+        assert!([0x02, 0x00, 0xc0, 0x0c]
+            .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+            .is_err());
+
+        // The record length exceeds the TLV.
+        let mut too_long = bytes.to_vec();
+        too_long[37] += 1;
+        assert!(too_long
+            .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+            .is_err());
+        // The record length doesn't cover the target.
+        let mut too_short = bytes.to_vec();
+        too_short[37] -= 1;
+        assert!(too_short
+            .pread::<ServiceResponseTLV<ReadLabelIterator>>(0)
+            .is_err());
+
+        // Bytes after the record aren't part of it.
+        let bytes = &include_bytes!("../../../../test_bins/service_response_tlv_txt.bin")[3..];
+        let mut trailing = bytes.to_vec();
+        trailing.extend_from_slice(b"\x04junk");
+        let mut offset = 0;
+        let service_response_tlv = trailing
+            .gread::<ServiceResponseTLV<ReadLabelIterator>>(&mut offset)
+            .unwrap();
+        assert_eq!(offset, bytes.len());
+        assert_eq!(
+            service_response_tlv
+                .record
+                .txt_entries()
+                .unwrap()
+                .map(|entry| entry.key)
+                .collect::<vec::Vec<_>>(),
+            ["flags"]
+        );
+    }
 }
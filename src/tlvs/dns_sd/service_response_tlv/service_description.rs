@@ -0,0 +1,194 @@
+use core::{iter::once, ops::Range};
+
+use scroll::Pwrite;
+
+use crate::common::{AWDLDnsCompression, AWDLDnsName, AWDLStr, ReadLabelIterator};
+
+use super::{
+    txt_record::{TxtEntries, TxtEntry},
+    DefaultServiceResponseTLV, ServiceResponseTLVBuilder,
+};
+
+/// Write the labels into the buffer and return the range they occupy.
+fn write_labels<'b>(
+    labels: impl IntoIterator<Item = AWDLStr<'b>>,
+    buf: &mut [u8],
+    offset: &mut usize,
+) -> Result<Range<usize>, scroll::Error> {
+    let start = *offset;
+    for label in labels {
+        buf.gwrite(label, offset)?;
+    }
+    Ok(start..*offset)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// A DNS-SD service instance, from which the [ServiceResponseTLV](super::ServiceResponseTLV)s advertising it are created.
+pub struct ServiceDescription<'a, T> {
+    /// The name of the instance, which is used as a single label and may contain dots.
+    pub instance_name: &'a str,
+    /// The type of the service like `_airdrop._tcp`, which is always in the `local` domain.
+    pub service_type: &'a str,
+    /// The port, on which the service is offered.
+    pub port: u16,
+    /// The host offering the service like `simon-framework.local`.
+    ///
+    /// Hosts outside of the `local` domain are kept as they are, without a compression.
+    pub target: &'a str,
+    /// The entries of the TXT record.
+    pub txt: T,
+}
+impl<'a, T> ServiceDescription<'a, T>
+where
+    T: IntoIterator<Item = TxtEntry<'a>>,
+{
+    /// Create the PTR, SRV and TXT records of the instance, with the best matching [compressions](AWDLDnsCompression).
+    ///
+    /// The labels and TXT record are encoded into the buffer, which the returned TLVs borrow from.
+    /// Like macOS, the target of the PTR record is only the instance name, since it's relative to the name of the service.
+    pub fn service_responses<'buf>(
+        self,
+        buf: &'buf mut [u8],
+    ) -> Result<[DefaultServiceResponseTLV<'buf>; 3], scroll::Error> {
+        let service_labels = self
+            .service_type
+            .trim_end_matches('.')
+            .split('.')
+            .chain(once("local"))
+            .map(AWDLStr::from);
+        let instance_labels = once(AWDLStr::from(self.instance_name)).chain(service_labels.clone());
+        let target_labels = self
+            .target
+            .trim_end_matches('.')
+            .split('.')
+            .map(AWDLStr::from);
+
+        let (service_remaining, service_domain) =
+            AWDLDnsCompression::best_suffix(service_labels.clone());
+        let (instance_remaining, instance_domain) =
+            AWDLDnsCompression::best_suffix(instance_labels.clone());
+        let (target_remaining, target_domain) =
+            AWDLDnsCompression::best_suffix(target_labels.clone());
+
+        let mut offset = 0;
+        let service_range = write_labels(service_labels.take(service_remaining), buf, &mut offset)?;
+        let instance_range =
+            write_labels(instance_labels.take(instance_remaining), buf, &mut offset)?;
+        let target_range = write_labels(target_labels.take(target_remaining), buf, &mut offset)?;
+        let txt_start = offset;
+        offset += TxtEntries::encode(self.txt, &mut buf[offset..])?;
+        let txt_range = txt_start..offset;
+
+        let buf: &'buf [u8] = buf;
+        let name = |range: Range<usize>, domain| AWDLDnsName {
+            labels: ReadLabelIterator::new(&buf[range]),
+            domain,
+        };
+        // The instance name is always the first label of the instance range.
+        let ptr_target = instance_range.start..instance_range.start + 1 + self.instance_name.len();
+        Ok([
+            ServiceResponseTLVBuilder::new()
+                .name(name(service_range, service_domain))
+                .ptr(name(ptr_target, AWDLDnsCompression::Null))
+                .build()?,
+            ServiceResponseTLVBuilder::new()
+                .name(name(instance_range.clone(), instance_domain))
+                .srv(self.port, name(target_range, target_domain))
+                .build()?,
+            ServiceResponseTLVBuilder::new()
+                .name(name(instance_range, instance_domain))
+                .txt(ReadLabelIterator::new(&buf[txt_range]))
+                .build()?,
+        ])
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_service_description() {
+    use alloc::vec;
+    use scroll::{ctx::MeasureWith, Pwrite};
+
+    use super::dns_record::AWDLDnsRecord;
+
+    let mut buf = [0x00; 0x100];
+    let [ptr, srv, txt] = ServiceDescription {
+        instance_name: "34fd6a0c9a42@1.021",
+        service_type: "_airplay-p2p._tcp",
+        port: 7000,
+        target: "dcc83dc2-fae7-4043-8c7a-a8b6bf49eaad.local",
+        txt: [],
+    }
+    .service_responses(&mut buf)
+    .unwrap();
+    assert_eq!(
+        ptr.name,
+        AWDLDnsName {
+            labels: ["_airplay-p2p".into()],
+            domain: AWDLDnsCompression::TcpLocal,
+        }
+    );
+    assert_eq!(
+        ptr.record,
+        AWDLDnsRecord::PTR {
+            domain_name: AWDLDnsName {
+                labels: ["34fd6a0c9a42@1.021".into()],
+                domain: AWDLDnsCompression::Null,
+            }
+        }
+    );
+    let mut written = vec![0x00; srv.measure_with(&())];
+    written.pwrite(srv, 0).unwrap();
+    assert_eq!(
+        written,
+        &include_bytes!("../../../../test_bins/service_response_tlv_srv.bin")[3..]
+    );
+    assert!(txt.record.txt_entries().unwrap().eq([]));
+
+    let [_, _, txt] = ServiceDescription {
+        instance_name: "6dba48462242",
+        service_type: "_airdrop._tcp",
+        port: 8770,
+        target: "simon-framework",
+        txt: [TxtEntry::new("flags", b"999")],
+    }
+    .service_responses(&mut buf)
+    .unwrap();
+    let mut written = vec![0x00; txt.measure_with(&())];
+    written.pwrite(txt, 0).unwrap();
+    assert_eq!(
+        written,
+        &include_bytes!("../../../../test_bins/service_response_tlv_txt.bin")[3..]
+    );
+
+    let [_, srv, _] = ServiceDescription {
+        instance_name: "printer",
+        service_type: "_ipp._tcp",
+        port: 631,
+        target: "host.example.com",
+        txt: [],
+    }
+    .service_responses(&mut buf)
+    .unwrap();
+    assert_eq!(
+        srv.record,
+        AWDLDnsRecord::SRV {
+            priority: 0,
+            weight: 0,
+            port: 631,
+            target: AWDLDnsName {
+                labels: ["host".into(), "example".into(), "com".into()],
+                domain: AWDLDnsCompression::Null,
+            }
+        }
+    );
+
+    assert!(ServiceDescription {
+        instance_name: "",
+        service_type: "_airdrop._tcp",
+        port: 8770,
+        target: "simon-framework",
+        txt: [],
+    }
+    .service_responses(&mut buf)
+    .is_err());
+}
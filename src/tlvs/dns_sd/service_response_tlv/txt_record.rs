@@ -0,0 +1,196 @@
+use core::str::from_utf8;
+
+use scroll::{
+    ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
+    Pread, Pwrite,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// A key/value entry of a DNS-SD TXT record, as specified in RFC 6763.
+pub struct TxtEntry<'a> {
+    /// The key, which is compared case insensitively.
+    pub key: &'a str,
+    /// The value, which may contain arbitrary bytes.
+    ///
+    /// This is [None] for boolean keys, which don't contain an `=`.
+    pub value: Option<&'a [u8]>,
+}
+impl<'a> TxtEntry<'a> {
+    /// Create an entry with a value.
+    pub const fn new(key: &'a str, value: &'a [u8]) -> Self {
+        Self {
+            key,
+            value: Some(value),
+        }
+    }
+    /// Create a boolean entry without a value.
+    pub const fn boolean(key: &'a str) -> Self {
+        Self { key, value: None }
+    }
+    /// Returns the value, if it's valid UTF-8.
+    pub fn value_str(&self) -> Option<&'a str> {
+        from_utf8(self.value?).ok()
+    }
+    /// Split the bytes of an entry into key and value.
+    ///
+    /// Entries with an empty key or a key, which isn't valid UTF-8, are rejected.
+    fn parse(bytes: &'a [u8]) -> Result<Self, scroll::Error> {
+        let (key, value) = match bytes.iter().position(|byte| *byte == b'=') {
+            Some(position) => (&bytes[..position], Some(&bytes[position + 1..])),
+            None => (bytes, None),
+        };
+        if key.is_empty() {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "TXT entries must have a key.",
+            });
+        }
+        let key = from_utf8(key).map_err(|_| scroll::Error::BadInput {
+            size: 0,
+            msg: "TXT key isn't valid UTF-8.",
+        })?;
+        Ok(Self { key, value })
+    }
+}
+impl MeasureWith<()> for TxtEntry<'_> {
+    fn measure_with(&self, _ctx: &()) -> usize {
+        1 + self.key.len() + self.value.map(|value| value.len() + 1).unwrap_or_default()
+    }
+}
+impl<'a> TryFromCtx<'a> for TxtEntry<'a> {
+    type Error = scroll::Error;
+    fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+        let length = from.gread::<u8>(&mut offset)? as usize;
+        let entry = Self::parse(from.gread_with(&mut offset, length)?)?;
+        Ok((entry, offset))
+    }
+}
+impl TryIntoCtx for TxtEntry<'_> {
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        if self.key.is_empty() || self.key.contains('=') {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "TXT keys must be non-empty and mustn't contain '='.",
+            });
+        }
+        let length = self.measure_with(&()) - 1;
+        if length > u8::MAX as usize {
+            return Err(scroll::Error::TooBig {
+                size: u8::MAX as usize,
+                len: length,
+            });
+        }
+        let mut offset = 0;
+        buf.gwrite(length as u8, &mut offset)?;
+        buf.gwrite(self.key.as_bytes(), &mut offset)?;
+        if let Some(value) = self.value {
+            buf.gwrite(b'=', &mut offset)?;
+            buf.gwrite(value, &mut offset)?;
+        }
+        Ok(offset)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// An iterator over the [entries](TxtEntry) of a TXT record.
+///
+/// Like specified in RFC 6763, entries without a key are skipped.
+pub struct TxtEntries<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+impl<'a> TxtEntries<'a> {
+    /// Create an iterator over the entries in the body of a TXT record.
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+    /// Returns the first entry with the key.
+    pub fn get(&self, key: &str) -> Option<TxtEntry<'a>> {
+        let mut entries = *self;
+        entries.find(|entry| entry.key.eq_ignore_ascii_case(key))
+    }
+    /// Encode the entries into the buffer.
+    ///
+    /// If there are no entries, a single empty string is written, since a TXT record mustn't be empty.
+    ///
+    /// Returns the number of bytes written.
+    pub fn encode<'b>(
+        entries: impl IntoIterator<Item = TxtEntry<'b>>,
+        buf: &mut [u8],
+    ) -> Result<usize, scroll::Error> {
+        let mut offset = 0;
+        for entry in entries {
+            buf.gwrite(entry, &mut offset)?;
+        }
+        if offset == 0 {
+            buf.gwrite(0u8, &mut offset)?;
+        }
+        Ok(offset)
+    }
+}
+impl<'a> Iterator for TxtEntries<'a> {
+    type Item = TxtEntry<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let length = self.bytes.gread::<u8>(&mut self.offset).ok()? as usize;
+            let bytes = self.bytes.gread_with(&mut self.offset, length).ok()?;
+            if let Ok(entry) = TxtEntry::parse(bytes) {
+                return Some(entry);
+            }
+        }
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_txt_entries() {
+    use alloc::vec::Vec;
+
+    use super::dns_record::AWDLDnsRecord;
+    use crate::common::ReadLabelIterator;
+
+    let bytes = b"\x09flags=999\x00\x03=ab\x04rast\x05pk=\xff\xfe\x03FV=";
+    let entries = TxtEntries::new(bytes);
+    assert_eq!(
+        entries.collect::<Vec<_>>(),
+        [
+            TxtEntry::new("flags", b"999"),
+            TxtEntry::boolean("rast"),
+            TxtEntry::new("pk", b"\xff\xfe"),
+            TxtEntry::new("FV", b""),
+        ]
+    );
+    assert_eq!(
+        entries.get("FLAGS").and_then(|entry| entry.value_str()),
+        Some("999")
+    );
+    assert_eq!(entries.get("pk").unwrap().value_str(), None);
+    assert_eq!(entries.get("rast").unwrap().value, None);
+    assert_eq!(entries.get("model"), None);
+
+    let mut buf = [0x00; 0x20];
+    let length = TxtEntries::encode(entries, &mut buf).unwrap();
+    assert_eq!(
+        &buf[..length],
+        b"\x09flags=999\x04rast\x05pk=\xff\xfe\x03FV="
+    );
+    assert_eq!(
+        buf.pread::<TxtEntry>(10).unwrap(),
+        TxtEntry::boolean("rast")
+    );
+
+    assert_eq!(TxtEntries::encode([], &mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 0x00);
+    assert!(TxtEntries::encode([TxtEntry::boolean("a=b")], &mut buf).is_err());
+    assert!(TxtEntries::encode([TxtEntry::new("model", &[0x00; 0x100])], &mut buf).is_err());
+
+    // Binary values are kept as they are.
+    let record = b"\x10\x05\x00\x00\x00\x04a=\xff\xfe"
+        .pread::<AWDLDnsRecord<ReadLabelIterator>>(0)
+        .unwrap();
+    assert_eq!(
+        record.txt_entries().unwrap().get("a"),
+        Some(TxtEntry::new("a", b"\xff\xfe"))
+    );
+}
@@ -2,8 +2,10 @@
 pub mod data_path;
 /// TLVs containing data about dns services.
 pub mod dns_sd;
+mod modified_tlvs;
 /// TLVs about the synchronization and election state of the peer.
 pub mod sync_elect;
+mod tlv_index;
 pub mod version;
 use core::{fmt::Debug, iter::repeat, marker::PhantomData};
 
@@ -17,6 +19,11 @@ use tlv_rs::{raw_tlv::RawTLV, TLV};
 
 use crate::common::{AWDLStr, ReadLabelIterator};
 
+pub use self::{
+    modified_tlvs::{ModifiedTLVs, TLVModification},
+    tlv_index::TLVIndex,
+};
+
 use self::{
     data_path::{DataPathStateTLV, HTCapabilitiesTLV, IEEE80211ContainerTLV},
     dns_sd::{ArpaTLV, ServiceResponseTLV},
@@ -86,7 +93,7 @@ pub enum AWDLTLV<'a, MACIterator, LabelIterator> {
     SynchronizationParameters(SynchronizationParametersTLV),
     ElectionParameters(ElectionParametersTLV),
     HTCapabilities(HTCapabilitiesTLV),
-    DataPathState(DataPathStateTLV),
+    DataPathState(DataPathStateTLV<'a>),
     Arpa(ArpaTLV<LabelIterator>),
     IEEE80211Container(IEEE80211ContainerTLV<'a>),
     ChannelSequence(ChannelSequenceTLV),
@@ -318,6 +325,31 @@ where
     }
 }
 
+macro_rules! from_impls {
+    ($($path:ident => $tlv:ty),*) => {
+        $(
+            impl<'a, MACIterator, LabelIterator> From<$tlv> for AWDLTLV<'a, MACIterator, LabelIterator> {
+                fn from(value: $tlv) -> Self {
+                    Self::$path(value)
+                }
+            }
+        )*
+    };
+}
+from_impls!(
+    ServiceResponse => ServiceResponseTLV<'a, LabelIterator>,
+    SynchronizationParameters => SynchronizationParametersTLV,
+    ElectionParameters => ElectionParametersTLV,
+    HTCapabilities => HTCapabilitiesTLV,
+    DataPathState => DataPathStateTLV<'a>,
+    Arpa => ArpaTLV<LabelIterator>,
+    IEEE80211Container => IEEE80211ContainerTLV<'a>,
+    ChannelSequence => ChannelSequenceTLV,
+    SynchronizationTree => SyncTreeTLV<MACIterator>,
+    Version => VersionTLV,
+    ElectionParametersV2 => ElectionParametersV2TLV
+);
+
 /// Default [AWDLTLV] returned by reading.
 pub type DefaultAWDLTLV<'a> = AWDLTLV<'a, ReadMACIterator<'a>, ReadLabelIterator<'a>>;
 
@@ -361,6 +393,10 @@ impl<'a> ReadTLVs<'a> {
         self.raw_tlv_iter()
             .find_map(|raw_tlv| self.match_and_parse_tlv(raw_tlv))
     }
+    /// Scan the TLVs once and build an [index](TLVIndex), which allows looking up TLVs without rescanning.
+    pub fn index(&self) -> TLVIndex<'a> {
+        TLVIndex::new(self.bytes)
+    }
 }
 impl MeasureWith<()> for ReadTLVs<'_> {
     fn measure_with(&self, _ctx: &()) -> usize {
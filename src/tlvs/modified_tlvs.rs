@@ -0,0 +1,226 @@
+use core::marker::PhantomData;
+
+use scroll::{
+    ctx::{MeasureWith, TryIntoCtx},
+    Endian, Pwrite,
+};
+
+use super::{AwdlTlv, ReadTLVs, TypedAWDLTLV};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A modification of a single TLV, which is applied by [ModifiedTLVs].
+pub enum TLVModification<Tlv> {
+    /// Replace the first TLV of the same type.
+    ///
+    /// If no TLV of that type is present, the TLV is appended.
+    Replace(Tlv),
+    /// Remove the first TLV of the type.
+    Remove,
+    /// Insert the TLV before the TLV at the position.
+    ///
+    /// If the position is past the last TLV, the TLV is appended.
+    Insert { position: usize, tlv: Tlv },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// TLVs read from an action frame with one typed TLV [modified](TLVModification).
+///
+/// All other TLVs are written back as raw bytes, without being parsed.
+pub struct ModifiedTLVs<'a, Tlv> {
+    pub tlvs: ReadTLVs<'a>,
+    pub modification: TLVModification<Tlv>,
+}
+impl<Tlv: AwdlTlv + MeasureWith<()>> MeasureWith<()> for ModifiedTLVs<'_, Tlv> {
+    fn measure_with(&self, ctx: &()) -> usize {
+        // Only the TLVs yielded by the raw TLV iterator are written, so trailing bytes aren't counted.
+        let (tlv, replace_or_remove) = match &self.modification {
+            TLVModification::Replace(tlv) => (Some(tlv), true),
+            TLVModification::Remove => (None, true),
+            TLVModification::Insert { tlv, .. } => (Some(tlv), false),
+        };
+        let mut matched = !replace_or_remove;
+        let raw_length = self
+            .tlvs
+            .raw_tlv_iter()
+            .filter(|raw_tlv| {
+                let is_replaced = !matched && raw_tlv.tlv_type == Tlv::TLV_TYPE.into_bits();
+                matched |= is_replaced;
+                !is_replaced
+            })
+            .map(|raw_tlv| 3 + raw_tlv.slice.len())
+            .sum::<usize>();
+        raw_length + tlv.map(|tlv| 3 + tlv.measure_with(ctx)).unwrap_or_default()
+    }
+}
+impl<Tlv: AwdlTlv + MeasureWith<()> + TryIntoCtx<Error = scroll::Error>> TryIntoCtx
+    for ModifiedTLVs<'_, Tlv>
+{
+    type Error = scroll::Error;
+    fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+        let (mut tlv, insert_position, replace_or_remove) = match self.modification {
+            TLVModification::Replace(tlv) => (Some(tlv), None, true),
+            TLVModification::Remove => (None, None, true),
+            TLVModification::Insert { position, tlv } => (Some(tlv), Some(position), false),
+        };
+        let mut matched = false;
+        let write_tlv = |tlv: Option<Tlv>, buf: &mut [u8], offset: &mut usize| {
+            let Some(payload) = tlv else {
+                return Ok(());
+            };
+            buf.gwrite_with(
+                TypedAWDLTLV {
+                    tlv_type: Tlv::TLV_TYPE,
+                    payload,
+                    _phantom: PhantomData,
+                },
+                offset,
+                Endian::Little,
+            )
+            .map(|_| ())
+        };
+        for (position, raw_tlv) in self.tlvs.raw_tlv_iter().enumerate() {
+            if insert_position == Some(position) {
+                write_tlv(tlv.take(), buf, &mut offset)?;
+            }
+            if replace_or_remove && !matched && raw_tlv.tlv_type == Tlv::TLV_TYPE.into_bits() {
+                matched = true;
+                write_tlv(tlv.take(), buf, &mut offset)?;
+                continue;
+            }
+            buf.gwrite_with(raw_tlv, &mut offset, Endian::Little)?;
+        }
+        // The TLV wasn't placed yet, so it's appended.
+        write_tlv(tlv, buf, &mut offset)?;
+        Ok(offset)
+    }
+}
+impl<'a> ReadTLVs<'a> {
+    /// Replace the first TLV of the same type, while keeping all other TLVs as they are.
+    ///
+    /// If no TLV of that type is present, the TLV is appended.
+    pub const fn replace_tlv<Tlv>(self, tlv: Tlv) -> ModifiedTLVs<'a, Tlv> {
+        ModifiedTLVs {
+            tlvs: self,
+            modification: TLVModification::Replace(tlv),
+        }
+    }
+    /// Remove the first TLV of that type, while keeping all other TLVs as they are.
+    pub const fn remove_tlv<Tlv>(self) -> ModifiedTLVs<'a, Tlv> {
+        ModifiedTLVs {
+            tlvs: self,
+            modification: TLVModification::Remove,
+        }
+    }
+    /// Insert the TLV before the TLV at the position, while keeping all other TLVs as they are.
+    pub const fn insert_tlv<Tlv>(self, position: usize, tlv: Tlv) -> ModifiedTLVs<'a, Tlv> {
+        ModifiedTLVs {
+            tlvs: self,
+            modification: TLVModification::Insert { position, tlv },
+        }
+    }
+}
+#[cfg(test)]
+mod modified_tlvs_tests {
+    use alloc::vec;
+    use scroll::{ctx::MeasureWith, Pread, Pwrite};
+
+    use crate::{
+        action_frame::DefaultAWDLActionFrame,
+        tlvs::{
+            dns_sd::DefaultArpaTLV, sync_elect::SynchronizationParametersTLV, version::VersionTLV,
+            AWDLTLVType, ReadTLVs,
+        },
+    };
+
+    fn read_tlvs(bytes: &[u8]) -> ReadTLVs<'_> {
+        bytes
+            .pread::<DefaultAWDLActionFrame>(0)
+            .unwrap()
+            .tagged_data
+    }
+    #[test]
+    fn test_replace_tlv() {
+        let bytes = include_bytes!("../../test_bins/mif.bin");
+        let tlvs = read_tlvs(bytes);
+        let mut sync_params = tlvs
+            .get_first_tlv::<SynchronizationParametersTLV>()
+            .unwrap();
+        sync_params.tx_counter += 1;
+
+        let modified_tlvs = tlvs.replace_tlv(sync_params.clone());
+        let mut buf = vec![0x00; modified_tlvs.measure_with(&())];
+        assert_eq!(buf.pwrite(modified_tlvs, 0).unwrap(), buf.len());
+
+        let written_tlvs = ReadTLVs::new(&buf);
+        assert_eq!(
+            written_tlvs.get_first_tlv::<SynchronizationParametersTLV>(),
+            Some(sync_params)
+        );
+        assert!(written_tlvs
+            .raw_tlv_iter()
+            .skip(1)
+            .eq(tlvs.raw_tlv_iter().skip(1)));
+
+        // Writing back an unchanged TLV has to yield the original bytes.
+        let modified_tlvs = tlvs.replace_tlv(tlvs.get_first_tlv::<DefaultArpaTLV>().unwrap());
+        let mut buf = vec![0x00; modified_tlvs.measure_with(&())];
+        buf.pwrite(modified_tlvs, 0).unwrap();
+        assert_eq!(buf, &bytes[12..]);
+    }
+    #[test]
+    fn test_remove_and_insert_tlv() {
+        let bytes = include_bytes!("../../test_bins/mif.bin");
+        let tlvs = read_tlvs(bytes);
+        let version_position = tlvs
+            .raw_tlv_iter()
+            .position(|raw_tlv| raw_tlv.tlv_type == AWDLTLVType::Version.into_bits())
+            .unwrap();
+        let version = tlvs.get_first_tlv::<VersionTLV>().unwrap();
+
+        let modified_tlvs = tlvs.remove_tlv::<VersionTLV>();
+        let mut removed = vec![0x00; modified_tlvs.measure_with(&())];
+        assert_eq!(removed.pwrite(modified_tlvs, 0).unwrap(), removed.len());
+        let removed_tlvs = ReadTLVs::new(&removed);
+        assert_eq!(removed_tlvs.get_first_tlv::<VersionTLV>(), None);
+        assert_eq!(
+            removed_tlvs.raw_tlv_iter().count() + 1,
+            tlvs.raw_tlv_iter().count()
+        );
+
+        let modified_tlvs = removed_tlvs.insert_tlv(version_position, version);
+        let mut inserted = vec![0x00; modified_tlvs.measure_with(&())];
+        inserted.pwrite(modified_tlvs, 0).unwrap();
+        assert_eq!(inserted, &bytes[12..]);
+
+        let modified_tlvs = removed_tlvs.insert_tlv(usize::MAX, version);
+        let mut appended = vec![0x00; modified_tlvs.measure_with(&())];
+        appended.pwrite(modified_tlvs, 0).unwrap();
+        assert_eq!(
+            ReadTLVs::new(&appended)
+                .raw_tlv_iter()
+                .last()
+                .unwrap()
+                .tlv_type,
+            AWDLTLVType::Version.into_bits()
+        );
+    }
+    #[test]
+    fn test_trailing_bytes() {
+        let mut bytes = include_bytes!("../../test_bins/mif.bin")[12..].to_vec();
+        let tlv_length = bytes.len();
+        // A truncated TLV header isn't yielded by the raw TLV iterator, so it isn't written back.
+        bytes.extend_from_slice(&[0x15, 0x02]);
+        let tlvs = ReadTLVs::new(&bytes);
+
+        let modified_tlvs = tlvs.remove_tlv::<VersionTLV>();
+        let mut buf = vec![0x00; modified_tlvs.measure_with(&())];
+        assert_eq!(buf.pwrite(modified_tlvs, 0).unwrap(), buf.len());
+        assert_eq!(buf.len(), tlv_length - 5);
+
+        let modified_tlvs = tlvs.replace_tlv(tlvs.get_first_tlv::<VersionTLV>().unwrap());
+        let mut buf = vec![0x00; modified_tlvs.measure_with(&())];
+        assert_eq!(buf.pwrite(modified_tlvs, 0).unwrap(), buf.len());
+        assert_eq!(buf, &bytes[..tlv_length]);
+    }
+}
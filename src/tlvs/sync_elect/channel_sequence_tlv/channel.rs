@@ -1,5 +1,7 @@
 use macro_bits::{bit, bitfield, serializable_enum};
 
+use super::op_class::{OperatingClass, OPERATING_CLASSES};
+
 serializable_enum! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
     /// This enum contains the three different types of channel encodings.
@@ -48,24 +50,47 @@ serializable_enum! {
         FourtyMHz => 0x02,
 
         /// 80MHz
-        EightyMHz => 0x03
+        EightyMHz => 0x03,
+
+        /// 160MHz
+        ///
+        /// No value for this has been observed in the legacy flags, so it lies outside of the field and can't be decoded from it.
+        OneHundredSixtyMHz => 0x04
+    }
+}
+impl ChannelBandwidth {
+    #[inline]
+    /// Returns the bandwidth in MHz.
+    pub const fn mhz(&self) -> Option<u16> {
+        match self {
+            Self::TwentyMHz => Some(20),
+            Self::FourtyMHz => Some(40),
+            Self::EightyMHz => Some(80),
+            Self::OneHundredSixtyMHz => Some(160),
+            Self::Unknown(_) => None,
+        }
     }
 }
 serializable_enum! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     /// This is the band on which the channel lies.
-    /// This could potentially be expanded to the 6GHz spectrum as well.
     pub enum Band : u8 {
         #[default]
         /// 2.4GHz
         TwoPointFourGHz => 0x02,
         /// 5GHz
-        FiveGHz => 0x01
+        FiveGHz => 0x01,
+        /// 6GHz
+        ///
+        /// Like [ChannelBandwidth::OneHundredSixtyMHz], this can't be decoded from the legacy flags.
+        SixGHz => 0x04
     }
 }
 bitfield! {
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
     /// The Flags for the legacy channel encoding.
+    ///
+    /// The channel is the center of the 40MHz channel containing the primary channel, unless the [support channel](SupportChannel) is [primary](SupportChannel::Primary).
     pub struct LegacyFlags : u8 {
         pub support_channel: SupportChannel => bit!(0, 1),
         pub channel_bandwidth: ChannelBandwidth => bit!(2, 3),
@@ -73,7 +98,7 @@ bitfield! {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// This enum contains a named channel.
 pub enum Channel {
     Simple { channel: u8 },
@@ -105,4 +130,193 @@ impl Channel {
             Self::OpClass { channel, .. } => *channel,
         }
     }
+    /// Returns the [operating class](OperatingClass) of the channel.
+    ///
+    /// Since [simple](Channel::Simple) channels carry no further information, they are assumed to be 20MHz wide.
+    pub fn operating_class(&self) -> Option<&'static OperatingClass> {
+        match self {
+            Self::Simple { channel } => OperatingClass::find(
+                if *channel <= 14 {
+                    Band::TwoPointFourGHz
+                } else {
+                    Band::FiveGHz
+                },
+                ChannelBandwidth::TwentyMHz,
+                SupportChannel::Primary,
+                *channel,
+            ),
+            Self::Legacy { flags, .. } => OperatingClass::find(
+                flags.band,
+                flags.channel_bandwidth,
+                flags.support_channel,
+                self.channel(),
+            ),
+            Self::OpClass { channel, opclass } => OperatingClass::from_opclass(*opclass)
+                .filter(|operating_class| operating_class.contains(*channel)),
+        }
+    }
+    /// Returns the band of the channel.
+    pub fn band(&self) -> Option<Band> {
+        self.operating_class()
+            .map(|operating_class| operating_class.band)
+    }
+    /// Returns the bandwidth of the channel.
+    pub fn bandwidth(&self) -> Option<ChannelBandwidth> {
+        self.operating_class()
+            .map(|operating_class| operating_class.bandwidth)
+    }
+    /// Returns the frequency of the primary channel in MHz.
+    pub fn primary_frequency(&self) -> Option<u16> {
+        self.operating_class()
+            .map(|operating_class| operating_class.frequency(self.channel()))
+    }
+    /// Returns the center frequency of the entire channel in MHz.
+    pub fn center_frequency(&self) -> Option<u16> {
+        let operating_class = self.operating_class()?;
+        Some(operating_class.frequency(operating_class.center_channel(self.channel())?))
+    }
+    /// Check if both channels have the same primary channel, independent of the encoding.
+    ///
+    /// If the frequency of one of the channels is unknown, the channel numbers are compared.
+    pub fn is_same_channel(&self, other: &Channel) -> bool {
+        match (self.primary_frequency(), other.primary_frequency()) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => self.channel() == other.channel(),
+        }
+    }
+    /// Convert the channel into another encoding.
+    ///
+    /// This returns [None], if the channel can't be represented in that encoding.
+    pub fn to_encoding(&self, channel_encoding: ChannelEncoding) -> Option<Self> {
+        if self.channel_encoding() == channel_encoding {
+            return Some(*self);
+        }
+        Self::from_frequency(
+            self.primary_frequency()?,
+            self.bandwidth()?,
+            channel_encoding,
+        )
+    }
+    /// Create a channel in the specified encoding from the frequency of the primary channel in MHz and the bandwidth.
+    ///
+    /// For 2.4GHz 40MHz channels, where the primary channel could be either the lower or the upper one, the lower one is chosen.
+    /// [Simple](Channel::Simple) channels can only be 20MHz wide and can't be on the 6GHz band.
+    /// [Legacy](Channel::Legacy) channels can't be 160MHz wide or on the 6GHz band, since the flags have no known value for them.
+    pub fn from_frequency(
+        primary_frequency: u16,
+        bandwidth: ChannelBandwidth,
+        channel_encoding: ChannelEncoding,
+    ) -> Option<Self> {
+        let (operating_class, channel) = OPERATING_CLASSES.iter().find_map(|operating_class| {
+            let channel = operating_class.channel(primary_frequency)?;
+            (operating_class.bandwidth == bandwidth && operating_class.contains(channel))
+                .then_some((operating_class, channel))
+        })?;
+        Some(match channel_encoding {
+            ChannelEncoding::Simple
+                if bandwidth == ChannelBandwidth::TwentyMHz
+                    && operating_class.band != Band::SixGHz =>
+            {
+                Self::Simple { channel }
+            }
+            ChannelEncoding::Legacy
+                if bandwidth != ChannelBandwidth::OneHundredSixtyMHz
+                    && operating_class.band != Band::SixGHz =>
+            {
+                let support_channel = operating_class.support_channel(channel)?;
+                Self::Legacy {
+                    flags: LegacyFlags {
+                        support_channel,
+                        channel_bandwidth: bandwidth,
+                        band: operating_class.band,
+                    },
+                    channel: match support_channel {
+                        SupportChannel::Lower => channel + 2,
+                        SupportChannel::Upper => channel - 2,
+                        _ => channel,
+                    },
+                }
+            }
+            ChannelEncoding::OpClass => Self::OpClass {
+                channel,
+                opclass: operating_class.opclass,
+            },
+            _ => return None,
+        })
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_channel_frequencies() {
+    let channel = Channel::OpClass {
+        channel: 44,
+        opclass: 0x80,
+    };
+    assert_eq!(channel.band(), Some(Band::FiveGHz));
+    assert_eq!(channel.bandwidth(), Some(ChannelBandwidth::EightyMHz));
+    assert_eq!(channel.primary_frequency(), Some(5220));
+    assert_eq!(channel.center_frequency(), Some(5210));
+    assert_eq!(
+        Channel::from_frequency(5220, ChannelBandwidth::EightyMHz, ChannelEncoding::OpClass),
+        Some(channel)
+    );
+
+    let channel = Channel::OpClass {
+        channel: 6,
+        opclass: 0x51,
+    };
+    assert_eq!(channel.primary_frequency(), Some(2437));
+    assert_eq!(channel.center_frequency(), Some(2437));
+    assert_eq!(
+        Channel::from_frequency(2437, ChannelBandwidth::TwentyMHz, ChannelEncoding::Simple),
+        Some(Channel::Simple { channel: 6 })
+    );
+    assert_eq!(
+        Channel::Simple { channel: 149 }.primary_frequency(),
+        Some(5745)
+    );
+
+    let channel =
+        Channel::from_frequency(5180, ChannelBandwidth::FourtyMHz, ChannelEncoding::Legacy)
+            .unwrap();
+    assert_eq!(
+        channel,
+        Channel::Legacy {
+            flags: LegacyFlags {
+                support_channel: SupportChannel::Lower,
+                channel_bandwidth: ChannelBandwidth::FourtyMHz,
+                band: Band::FiveGHz
+            },
+            channel: 38
+        }
+    );
+    assert_eq!(channel.channel(), 36);
+    assert_eq!(
+        Channel::from_frequency(5220, ChannelBandwidth::EightyMHz, ChannelEncoding::Legacy),
+        Some(Channel::Legacy {
+            flags: LegacyFlags {
+                support_channel: SupportChannel::Lower,
+                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                band: Band::FiveGHz
+            },
+            channel: 46
+        })
+    );
+    assert_eq!(channel.center_frequency(), Some(5190));
+    // The unused values of the legacy flags aren't assigned to 160MHz or 6GHz.
+    let flags = LegacyFlags::from_bits(0b0011_0011);
+    assert_eq!(flags.channel_bandwidth, ChannelBandwidth::Unknown(0x00));
+    assert_eq!(flags.band, Band::Unknown(0x03));
+    assert_eq!(
+        Channel::from_frequency(5180, ChannelBandwidth::FourtyMHz, ChannelEncoding::Simple),
+        None
+    );
+    assert_eq!(
+        Channel::OpClass {
+            channel: 44,
+            opclass: 0x51
+        }
+        .operating_class(),
+        None
+    );
 }
@@ -1,4 +1,8 @@
-use core::fmt::Debug;
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
 use scroll::{
     ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
     Pread, Pwrite,
@@ -6,17 +10,108 @@ use scroll::{
 
 use super::channel::*;
 
-#[derive(Clone, PartialEq, Eq)]
+/// The amount of channels in the channel sequences sent by macOS and iOS, which is the default capacity.
+pub const DEFAULT_CHANNEL_SEQUENCE_LENGTH: usize = 16;
+/// The maximum amount of channels in a channel sequence, since the count is encoded as a [u8] minus one.
+///
+/// Sequences of any length can be parsed by using this as the capacity.
+pub const MAX_CHANNEL_SEQUENCE_LENGTH: usize = 256;
+
+#[derive(Clone, Copy)]
+/// A fixed capacity array holding up to `N` channels.
+///
+/// This dereferences to a slice of the channels, that are actually present.
+pub struct ChannelArray<T, const N: usize> {
+    channels: [T; N],
+    length: usize,
+}
+impl<T: Copy + Default, const N: usize> ChannelArray<T, N> {
+    /// Create an empty array.
+    pub fn new() -> Self {
+        Self {
+            channels: [T::default(); N],
+            length: 0,
+        }
+    }
+    /// Create an array from the channels.
+    ///
+    /// This returns [None], if there are more than `N` channels.
+    pub fn from_slice(channels: &[T]) -> Option<Self> {
+        if channels.len() > N {
+            return None;
+        }
+        let mut array = [T::default(); N];
+        array[..channels.len()].copy_from_slice(channels);
+        Some(Self {
+            channels: array,
+            length: channels.len(),
+        })
+    }
+}
+impl<T, const N: usize> ChannelArray<T, N> {
+    /// Append a channel.
+    ///
+    /// If the array is full, the channel is returned.
+    pub fn push(&mut self, channel: T) -> Result<(), T> {
+        match self.channels.get_mut(self.length) {
+            Some(slot) => {
+                *slot = channel;
+                self.length += 1;
+                Ok(())
+            }
+            None => Err(channel),
+        }
+    }
+}
+impl<T: Copy + Default, const N: usize> Default for ChannelArray<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> From<[T; N]> for ChannelArray<T, N> {
+    fn from(channels: [T; N]) -> Self {
+        Self {
+            channels,
+            length: N,
+        }
+    }
+}
+impl<T, const N: usize> Deref for ChannelArray<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        &self.channels[..self.length]
+    }
+}
+impl<T: PartialEq, const N: usize> PartialEq for ChannelArray<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+impl<T: Eq, const N: usize> Eq for ChannelArray<T, N> {}
+impl<T: Hash, const N: usize> Hash for ChannelArray<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state)
+    }
+}
+impl<T: Debug, const N: usize> Debug for ChannelArray<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 /// The different types of channel sequences.
-pub enum ChannelSequence {
+///
+/// A channel sequence can hold up to `N` channels.
+pub enum ChannelSequence<const N: usize = DEFAULT_CHANNEL_SEQUENCE_LENGTH> {
     /// This en'codes just the channel.
-    Simple([u8; 16]),
+    Simple(ChannelArray<u8, N>),
     /// This encodes channel flags and the channel it self.
-    Legacy([(LegacyFlags, u8); 16]),
+    Legacy(ChannelArray<(LegacyFlags, u8), N>),
     /// This encodes first the channel and then the channels opclass.
-    OpClass([(u8, u8); 16]),
+    OpClass(ChannelArray<(u8, u8), N>),
 }
-impl ChannelSequence {
+impl<const N: usize> ChannelSequence<N> {
     #[inline]
     /// Returns the channel encoding of the channel sequence.
     pub const fn channel_encoding(&self) -> ChannelEncoding {
@@ -28,67 +123,218 @@ impl ChannelSequence {
     }
     #[inline]
     /// Generates a repeating channel sequence with the argument.
+    ///
+    /// Like macOS and iOS, the sequence has [DEFAULT_CHANNEL_SEQUENCE_LENGTH] channels, unless the capacity is smaller.
     pub const fn fixed_channel_sequence(channel: Channel) -> Self {
+        let length = if N < DEFAULT_CHANNEL_SEQUENCE_LENGTH {
+            N
+        } else {
+            DEFAULT_CHANNEL_SEQUENCE_LENGTH
+        };
         match channel {
-            Channel::Simple { channel } => ChannelSequence::Simple([channel; 16]),
-            Channel::Legacy { flags, channel } => ChannelSequence::Legacy([(flags, channel); 16]),
-            Channel::OpClass { channel, opclass } => {
-                ChannelSequence::OpClass([(channel, opclass); 16])
+            Channel::Simple { channel } => Self::Simple(ChannelArray {
+                channels: [channel; N],
+                length,
+            }),
+            Channel::Legacy { flags, channel } => Self::Legacy(ChannelArray {
+                channels: [(flags, channel); N],
+                length,
+            }),
+            Channel::OpClass { channel, opclass } => Self::OpClass(ChannelArray {
+                channels: [(channel, opclass); N],
+                length,
+            }),
+        }
+    }
+    #[inline]
+    /// Returns the amount of channels in the sequence.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Simple(channels) => channels.len(),
+            Self::Legacy(channels) => channels.len(),
+            Self::OpClass(channels) => channels.len(),
+        }
+    }
+    #[inline]
+    /// Check if the sequence doesn't contain any channels.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Create a sequence in the specified encoding from the channels.
+    ///
+    /// Channels in other encodings are [converted](Channel::to_encoding).
+    /// This returns [None], if a channel can't be converted or there are more than `N` channels.
+    pub fn from_channels(
+        channel_encoding: ChannelEncoding,
+        channels: impl IntoIterator<Item = Channel>,
+    ) -> Option<Self> {
+        let mut channel_sequence = match channel_encoding {
+            ChannelEncoding::Simple => Self::Simple(ChannelArray::new()),
+            ChannelEncoding::Legacy => Self::Legacy(ChannelArray::new()),
+            ChannelEncoding::OpClass => Self::OpClass(ChannelArray::new()),
+            ChannelEncoding::Unknown(_) => return None,
+        };
+        for channel in channels {
+            match (
+                &mut channel_sequence,
+                channel.to_encoding(channel_encoding)?,
+            ) {
+                (Self::Simple(channels), Channel::Simple { channel }) => {
+                    channels.push(channel).ok()?
+                }
+                (Self::Legacy(channels), Channel::Legacy { flags, channel }) => {
+                    channels.push((flags, channel)).ok()?
+                }
+                (Self::OpClass(channels), Channel::OpClass { channel, opclass }) => {
+                    channels.push((channel, opclass)).ok()?
+                }
+                _ => return None,
             }
         }
+        Some(channel_sequence)
+    }
+    /// Convert the sequence into another encoding.
+    pub fn to_encoding(&self, channel_encoding: ChannelEncoding) -> Option<Self> {
+        Self::from_channels(channel_encoding, self.iter())
+    }
+    /// Returns the channel at that position in the sequence.
+    pub fn get(&self, index: usize) -> Option<Channel> {
+        Some(match self {
+            Self::Simple(channels) => Channel::Simple {
+                channel: *channels.get(index)?,
+            },
+            Self::Legacy(channels) => {
+                let (flags, channel) = *channels.get(index)?;
+                Channel::Legacy { flags, channel }
+            }
+            Self::OpClass(channels) => {
+                let (channel, opclass) = *channels.get(index)?;
+                Channel::OpClass { channel, opclass }
+            }
+        })
+    }
+    /// Returns an iterator over the channels, independent of the encoding.
+    pub fn iter(&self) -> impl Iterator<Item = Channel> + Clone + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+    /// Returns an iterator over the runs of consecutive identical channels and their length.
+    ///
+    /// This is the compressed form of the sequence, in which it's usually displayed.
+    pub fn runs(&self) -> impl Iterator<Item = (Channel, usize)> + '_ {
+        let mut channels = self.iter().peekable();
+        core::iter::from_fn(move || {
+            let channel = channels.next()?;
+            let mut length = 1;
+            while channels.next_if_eq(&channel).is_some() {
+                length += 1;
+            }
+            Some((channel, length))
+        })
+    }
+    /// Returns the channel, which is used in that AW.
+    ///
+    /// Every channel in the sequence is used for one AW, after which the sequence repeats.
+    pub fn channel_at(&self, aw_index: usize) -> Option<Channel> {
+        if self.is_empty() {
+            return None;
+        }
+        self.get(aw_index % self.len())
+    }
+    /// Returns an iterator over the channels in the sequence, with duplicates removed.
+    pub fn distinct_channels(&self) -> impl Iterator<Item = Channel> + '_ {
+        self.iter().enumerate().filter_map(|(index, channel)| {
+            self.iter()
+                .position(|other| other.is_same_channel(&channel))
+                .filter(|position| *position == index)
+                .map(|_| channel)
+        })
+    }
+    /// Returns the fraction of AWs spent on the channel.
+    pub fn fraction_on(&self, channel: &Channel) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.iter()
+            .filter(|other| other.is_same_channel(channel))
+            .count() as f32
+            / self.len() as f32
+    }
+    /// Returns an iterator over the AWs, in which both sequences are on the same channel.
+    ///
+    /// If the sequences differ in length, the shorter one is repeated.
+    pub fn common_slots<'a, const O: usize>(
+        &'a self,
+        other: &'a ChannelSequence<O>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        (0..self.len().max(other.len())).filter(|aw_index| {
+            match (self.channel_at(*aw_index), other.channel_at(*aw_index)) {
+                (Some(lhs), Some(rhs)) => lhs.is_same_channel(&rhs),
+                _ => false,
+            }
+        })
     }
 }
-impl Default for ChannelSequence {
+impl<const N: usize> Default for ChannelSequence<N> {
     fn default() -> Self {
-        ChannelSequence::Simple(Default::default())
+        Self::fixed_channel_sequence(Channel::Simple { channel: 0 })
     }
 }
-impl Debug for ChannelSequence {
+impl<const N: usize> Debug for ChannelSequence<N> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self {
-            ChannelSequence::Simple(channels) => f.debug_list().entries(channels.iter()).finish(),
-            ChannelSequence::Legacy(channels) => f
-                .debug_list()
-                .entries(channels.iter().map(|(_, channel)| channel))
-                .finish(),
-            ChannelSequence::OpClass(channels) => f
-                .debug_list()
-                .entries(channels.iter().map(|(channel, _)| channel))
-                .finish(),
-        }
+        f.debug_list().entries(self.iter()).finish()
     }
 }
-impl MeasureWith<()> for ChannelSequence {
+impl<const N: usize> MeasureWith<()> for ChannelSequence<N> {
     fn measure_with(&self, _ctx: &()) -> usize {
-        16 * match self {
-            ChannelSequence::Legacy(_) | ChannelSequence::OpClass(_) => 2,
-            _ => 1,
-        }
+        self.len() * self.channel_encoding().size() as usize
     }
 }
-impl<'a> TryFromCtx<'a, ChannelEncoding> for ChannelSequence {
+impl<'a, const N: usize> TryFromCtx<'a, (ChannelEncoding, usize)> for ChannelSequence<N> {
     type Error = scroll::Error;
     fn try_from_ctx(
         from: &'a [u8],
-        encoding: ChannelEncoding,
+        (encoding, channel_count): (ChannelEncoding, usize),
     ) -> Result<(Self, usize), Self::Error> {
+        // The length isn't part of the sequence, so it has to be passed in through the context.
+        if channel_count > N {
+            return Err(scroll::Error::TooBig {
+                size: channel_count,
+                len: N,
+            });
+        }
         let mut offset = 0;
         Ok((
             match encoding {
-                ChannelEncoding::Simple => {
-                    ChannelSequence::Simple(from.gread::<[u8; 16]>(&mut offset)?)
-                }
+                ChannelEncoding::Simple => ChannelSequence::Simple({
+                    let mut array = ChannelArray {
+                        channels: [0; N],
+                        length: channel_count,
+                    };
+                    for channel in array.channels[..channel_count].iter_mut() {
+                        *channel = from.gread(&mut offset)?;
+                    }
+                    array
+                }),
                 ChannelEncoding::Legacy => ChannelSequence::Legacy({
-                    let mut array = [(LegacyFlags::default(), 0); 16];
-                    for (i, bytes) in from.gread::<[u8; 32]>(&mut offset)?.chunks(2).enumerate() {
-                        array[i] = (LegacyFlags::from_bits(bytes[0]), bytes[1]);
+                    let mut array = ChannelArray {
+                        channels: [(LegacyFlags::default(), 0); N],
+                        length: channel_count,
+                    };
+                    for channel in array.channels[..channel_count].iter_mut() {
+                        *channel = (
+                            LegacyFlags::from_bits(from.gread(&mut offset)?),
+                            from.gread(&mut offset)?,
+                        );
                     }
                     array
                 }),
                 ChannelEncoding::OpClass => ChannelSequence::OpClass({
-                    let mut array = [(0, 0); 16];
-                    for (i, bytes) in from.gread::<[u8; 32]>(&mut offset)?.chunks(2).enumerate() {
-                        array[i] = (bytes[0], bytes[1]);
+                    let mut array = ChannelArray {
+                        channels: [(0, 0); N],
+                        length: channel_count,
+                    };
+                    for channel in array.channels[..channel_count].iter_mut() {
+                        *channel = (from.gread(&mut offset)?, from.gread(&mut offset)?);
                     }
                     array
                 }),
@@ -103,11 +349,11 @@ impl<'a> TryFromCtx<'a, ChannelEncoding> for ChannelSequence {
         ))
     }
 }
-impl TryIntoCtx for ChannelSequence {
+impl<const N: usize> TryIntoCtx for ChannelSequence<N> {
     type Error = scroll::Error;
     fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
         match self {
-            ChannelSequence::Simple(channels) => buf.pwrite::<&[u8]>(channels.as_ref(), 0),
+            ChannelSequence::Simple(channels) => buf.pwrite::<&[u8]>(&channels, 0),
             ChannelSequence::Legacy(channels) => {
                 let mut offset = 0;
                 for (flags, channel) in channels.iter() {
@@ -127,3 +373,56 @@ impl TryIntoCtx for ChannelSequence {
         }
     }
 }
+#[cfg(test)]
+#[test]
+fn test_channel_sequence_queries() {
+    use alloc::{format, vec::Vec};
+
+    let high_band = Channel::OpClass {
+        channel: 149,
+        opclass: 0x80,
+    };
+    let low_band = Channel::OpClass {
+        channel: 6,
+        opclass: 0x51,
+    };
+    let channel_sequence = ChannelSequence::<16>::from_channels(
+        ChannelEncoding::OpClass,
+        [high_band; 8].into_iter().chain([low_band; 8]),
+    )
+    .unwrap();
+    assert_eq!(channel_sequence.iter().count(), 16);
+    assert_eq!(channel_sequence.channel_at(17), Some(high_band));
+    assert_eq!(channel_sequence.channel_at(8), Some(low_band));
+    assert!(channel_sequence
+        .distinct_channels()
+        .eq([high_band, low_band]));
+    assert!(channel_sequence.runs().eq([(high_band, 8), (low_band, 8)]));
+    assert_eq!(channel_sequence.fraction_on(&low_band), 0.5);
+
+    let legacy_sequence = channel_sequence
+        .to_encoding(ChannelEncoding::Legacy)
+        .unwrap();
+    assert_eq!(legacy_sequence.channel_encoding(), ChannelEncoding::Legacy);
+    assert_eq!(
+        legacy_sequence.to_encoding(ChannelEncoding::OpClass),
+        Some(channel_sequence)
+    );
+    assert_eq!(legacy_sequence.fraction_on(&high_band), 0.5);
+    // The 80MHz channel can't be expressed in the simple encoding.
+    assert_eq!(channel_sequence.to_encoding(ChannelEncoding::Simple), None);
+
+    let other_sequence = ChannelSequence::<4>::from_channels(
+        ChannelEncoding::Simple,
+        [Channel::Simple { channel: 6 }],
+    )
+    .unwrap();
+    assert_eq!(
+        channel_sequence
+            .common_slots(&other_sequence)
+            .collect::<Vec<_>>(),
+        (8..16).collect::<Vec<_>>()
+    );
+    assert!(format!("{legacy_sequence:?}").contains("channel_bandwidth: EightyMHz"));
+    assert!(ChannelSequence::<1>::from_channels(ChannelEncoding::OpClass, [low_band; 2]).is_none());
+}
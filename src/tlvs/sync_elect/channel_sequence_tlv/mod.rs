@@ -1,5 +1,6 @@
 pub mod channel;
 pub mod channel_sequence;
+pub mod op_class;
 
 use core::num::NonZeroU8;
 
@@ -14,43 +15,44 @@ use scroll::{
 use crate::tlvs::{AWDLTLVType, AwdlTlv};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct ChannelSequenceTLV {
+/// The channel sequence of a peer, which can hold up to `N` channels.
+pub struct ChannelSequenceTLV<const N: usize = DEFAULT_CHANNEL_SEQUENCE_LENGTH> {
     /// The amount of AWs spent on one channel.
     pub step_count: NonZeroU8,
 
+    /// The duplicate count.
+    ///
+    /// The purpose of this is unknown, but it's preserved for round trips.
+    pub duplicate_count: u8,
+
     /// The channels.
-    pub channel_sequence: ChannelSequence,
+    pub channel_sequence: ChannelSequence<N>,
 }
-impl AwdlTlv for ChannelSequenceTLV {
+impl<const N: usize> AwdlTlv for ChannelSequenceTLV<N> {
     const TLV_TYPE: AWDLTLVType = AWDLTLVType::ChannelSequence;
 }
-impl Default for ChannelSequenceTLV {
+impl<const N: usize> Default for ChannelSequenceTLV<N> {
     fn default() -> Self {
         ChannelSequenceTLV {
             step_count: NonZeroU8::new(3).unwrap(),
+            duplicate_count: 0,
             channel_sequence: Default::default(),
         }
     }
 }
-impl MeasureWith<()> for ChannelSequenceTLV {
+impl<const N: usize> MeasureWith<()> for ChannelSequenceTLV<N> {
     fn measure_with(&self, ctx: &()) -> usize {
         9 + self.channel_sequence.measure_with(ctx)
     }
 }
-impl<'a> TryFromCtx<'a> for ChannelSequenceTLV {
+impl<'a, const N: usize> TryFromCtx<'a> for ChannelSequenceTLV<N> {
     type Error = scroll::Error;
     fn try_from_ctx(from: &'a [u8], _ctx: ()) -> Result<(Self, usize), Self::Error> {
         let mut offset = 0;
 
-        let channel_count = from.gread::<u8>(&mut offset)? + 1;
-        if channel_count != 16 {
-            return Err(scroll::Error::BadInput {
-                size: offset,
-                msg: "Channel sequence length wasn't 16.",
-            });
-        }
+        let channel_count = from.gread::<u8>(&mut offset)? as usize + 1;
         let channel_encoding = ChannelEncoding::from_bits(from.gread(&mut offset)?);
-        offset += 1; // Skip duplicate count
+        let duplicate_count = from.gread(&mut offset)?;
         let step_count = NonZeroU8::new(from.gread::<u8>(&mut offset)?.checked_add(1).ok_or(
             scroll::Error::BadInput {
                 size: offset,
@@ -59,28 +61,36 @@ impl<'a> TryFromCtx<'a> for ChannelSequenceTLV {
         )?)
         .unwrap();
         offset += 2;
-        let channel_sequence = from.gread_with(&mut offset, channel_encoding)?;
+        let channel_sequence = from.gread_with(&mut offset, (channel_encoding, channel_count))?;
 
         Ok((
             Self {
                 step_count,
+                duplicate_count,
                 channel_sequence,
             },
             offset,
         ))
     }
 }
-impl TryIntoCtx for ChannelSequenceTLV {
+impl<const N: usize> TryIntoCtx for ChannelSequenceTLV<N> {
     type Error = scroll::Error;
     fn try_into_ctx(self, buf: &mut [u8], _ctx: ()) -> Result<usize, Self::Error> {
         let mut offset = 0;
 
-        buf.gwrite(16u8 - 1, &mut offset)?;
+        let channel_count = self.channel_sequence.len();
+        if !(1..=256).contains(&channel_count) {
+            return Err(scroll::Error::BadInput {
+                size: offset,
+                msg: "Channel sequence length has to be between 1 and 256.",
+            });
+        }
+        buf.gwrite((channel_count - 1) as u8, &mut offset)?;
         buf.gwrite(
             self.channel_sequence.channel_encoding().into_bits(),
             &mut offset,
         )?;
-        offset += 1;
+        buf.gwrite(self.duplicate_count, &mut offset)?;
         buf.gwrite(self.step_count.get() - 1, &mut offset)?;
         buf.gwrite(0xffffu16, &mut offset)?;
         buf.gwrite(self.channel_sequence, &mut offset)?;
@@ -100,6 +110,7 @@ fn test_channel_sequence_tlv() {
         channel_sequence_tlv,
         ChannelSequenceTLV {
             step_count: NonZeroU8::new(4).unwrap(),
+            duplicate_count: 0,
             channel_sequence: ChannelSequence::fixed_channel_sequence(Channel::OpClass {
                 channel: 0x6,
                 opclass: 0x51
@@ -110,3 +121,55 @@ fn test_channel_sequence_tlv() {
     buf.as_mut_slice().pwrite(channel_sequence_tlv, 0).unwrap();
     assert_eq!(buf, bytes);
 }
+#[cfg(test)]
+#[test]
+fn test_variable_length_channel_sequence_tlv() {
+    use alloc::vec;
+
+    let bytes = [
+        0x02, 0x03, 0x01, 0x03, 0xff, 0xff, 0x95, 0x80, 0x95, 0x80, 0x06, 0x51, 0x00, 0x00, 0x00,
+    ];
+    let channel_sequence_tlv = bytes.pread::<ChannelSequenceTLV>(0).unwrap();
+    assert_eq!(
+        channel_sequence_tlv,
+        ChannelSequenceTLV {
+            step_count: NonZeroU8::new(4).unwrap(),
+            duplicate_count: 1,
+            channel_sequence: ChannelSequence::OpClass(
+                ChannelArray::from_slice(&[(0x95, 0x80), (0x95, 0x80), (0x06, 0x51)]).unwrap()
+            ),
+        }
+    );
+    assert_eq!(channel_sequence_tlv.channel_sequence.len(), 3);
+    let mut buf = vec![0x00; channel_sequence_tlv.measure_with(&())];
+    buf.as_mut_slice()
+        .pwrite(channel_sequence_tlv.clone(), 0)
+        .unwrap();
+    assert_eq!(buf, bytes);
+
+    assert!(bytes.pread::<ChannelSequenceTLV<2>>(0).is_err());
+    assert!(buf
+        .pwrite(
+            ChannelSequenceTLV {
+                channel_sequence: ChannelSequence::Simple(ChannelArray::from_slice(&[]).unwrap()),
+                ..channel_sequence_tlv
+            },
+            0
+        )
+        .is_err());
+}
+#[cfg(test)]
+#[test]
+fn test_maximum_length_channel_sequence_tlv() {
+    use alloc::vec::Vec;
+
+    // The 256 channels, which can be encoded, only fit with the maximum capacity.
+    let mut bytes = Vec::from([0xff, 0x00, 0x00, 0x03, 0xff, 0xff]);
+    bytes.extend((0..=u8::MAX).map(|channel| channel % 14 + 1));
+    bytes.extend_from_slice(&[0x00; 3]);
+    assert!(bytes.pread::<ChannelSequenceTLV>(0).is_err());
+    let channel_sequence_tlv = bytes
+        .pread::<ChannelSequenceTLV<MAX_CHANNEL_SEQUENCE_LENGTH>>(0)
+        .unwrap();
+    assert_eq!(channel_sequence_tlv.channel_sequence.len(), 256);
+}
@@ -0,0 +1,451 @@
+use super::channel::{Band, ChannelBandwidth, SupportChannel};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// An operating class from the global operating class table (IEEE 802.11 Annex E, Table E-4).
+pub struct OperatingClass {
+    /// The number of the operating class.
+    pub opclass: u8,
+    /// The band of the operating class.
+    pub band: Band,
+    /// The bandwidth of the channels.
+    pub bandwidth: ChannelBandwidth,
+    /// The frequency of channel zero in MHz.
+    pub starting_frequency: u16,
+    /// The position of the primary channel inside a 40MHz channel.
+    ///
+    /// This is [SupportChannel::Primary] for all other bandwidths and for operating classes, which specify the center channels of 40MHz channels.
+    pub primary_position: SupportChannel,
+    /// The channel set.
+    ///
+    /// Like in the standard, these are center channels, if the [primary position](Self::primary_position) is [SupportChannel::Primary] and the channels are wider than 20MHz.
+    /// Otherwise these are primary channels.
+    pub channel_set: &'static [u8],
+}
+impl OperatingClass {
+    const fn new(
+        opclass: u8,
+        band: Band,
+        bandwidth: ChannelBandwidth,
+        starting_frequency: u16,
+        primary_position: SupportChannel,
+        channel_set: &'static [u8],
+    ) -> Self {
+        Self {
+            opclass,
+            band,
+            bandwidth,
+            starting_frequency,
+            primary_position,
+            channel_set,
+        }
+    }
+    /// Look up the operating class by its number.
+    pub fn from_opclass(opclass: u8) -> Option<&'static Self> {
+        OPERATING_CLASSES
+            .iter()
+            .find(|operating_class| operating_class.opclass == opclass)
+    }
+    /// Look up the operating class containing the primary channel.
+    ///
+    /// The primary position is only taken into account for 40MHz channels, where it isn't [SupportChannel::Primary].
+    pub fn find(
+        band: Band,
+        bandwidth: ChannelBandwidth,
+        primary_position: SupportChannel,
+        primary_channel: u8,
+    ) -> Option<&'static Self> {
+        OPERATING_CLASSES.iter().find(|operating_class| {
+            operating_class.band == band
+                && operating_class.bandwidth == bandwidth
+                && (bandwidth != ChannelBandwidth::FourtyMHz
+                    || primary_position == SupportChannel::Primary
+                    || operating_class.primary_position == SupportChannel::Primary
+                    || operating_class.primary_position == primary_position)
+                && operating_class.contains(primary_channel)
+        })
+    }
+    /// Check if the primary channel is part of the operating class.
+    pub fn contains(&self, primary_channel: u8) -> bool {
+        self.center_channel(primary_channel).is_some()
+    }
+    /// Returns the offset of the primary channel from the lowest channel number of the channel, if the channel set contains center channels.
+    fn primary_offset(&self, primary_channel: u8) -> Option<(u8, i16)> {
+        // Channel numbers are spaced 5MHz apart, while the 20MHz channels are four channel numbers apart.
+        let span = (self.bandwidth.mhz()? / 5) as i16;
+        self.channel_set.iter().find_map(|center_channel| {
+            let offset = primary_channel as i16 - (*center_channel as i16 - span / 2 + 2);
+            ((0..span).contains(&offset) && offset % 4 == 0).then_some((*center_channel, offset))
+        })
+    }
+    /// Returns the center channel of the channel with that primary channel.
+    pub fn center_channel(&self, primary_channel: u8) -> Option<u8> {
+        match (self.bandwidth, self.primary_position) {
+            (ChannelBandwidth::TwentyMHz, _) => self
+                .channel_set
+                .contains(&primary_channel)
+                .then_some(primary_channel),
+            (_, SupportChannel::Lower) if self.channel_set.contains(&primary_channel) => {
+                primary_channel.checked_add(2)
+            }
+            (_, SupportChannel::Upper) if self.channel_set.contains(&primary_channel) => {
+                primary_channel.checked_sub(2)
+            }
+            (_, SupportChannel::Primary) => self
+                .primary_offset(primary_channel)
+                .map(|(center_channel, _)| center_channel),
+            _ => None,
+        }
+    }
+    /// Returns the position of the primary channel inside the 40MHz channel containing it.
+    ///
+    /// For 20MHz channels this is [SupportChannel::Primary].
+    pub fn support_channel(&self, primary_channel: u8) -> Option<SupportChannel> {
+        match (self.bandwidth, self.primary_position) {
+            (ChannelBandwidth::TwentyMHz, _) => self
+                .contains(primary_channel)
+                .then_some(SupportChannel::Primary),
+            (_, SupportChannel::Primary) => {
+                let (_, offset) = self.primary_offset(primary_channel)?;
+                Some(if offset % 8 == 0 {
+                    SupportChannel::Lower
+                } else {
+                    SupportChannel::Upper
+                })
+            }
+            (_, primary_position) => self.contains(primary_channel).then_some(primary_position),
+        }
+    }
+    /// Returns the frequency of the channel in MHz.
+    pub const fn frequency(&self, channel: u8) -> u16 {
+        self.starting_frequency + channel as u16 * 5
+    }
+    /// Returns the channel with that frequency in MHz.
+    pub const fn channel(&self, frequency: u16) -> Option<u8> {
+        if frequency < self.starting_frequency
+            || !(frequency - self.starting_frequency).is_multiple_of(5)
+        {
+            return None;
+        }
+        let channel = (frequency - self.starting_frequency) / 5;
+        if channel > u8::MAX as u16 {
+            None
+        } else {
+            Some(channel as u8)
+        }
+    }
+}
+/// The operating classes from the global operating class table, which can be represented in AWDL.
+///
+/// The 80+80MHz operating classes are missing, since AWDL has no way of encoding the second segment.
+/// The 320MHz operating class is missing as well, since no bandwidth for it exists in AWDL.
+pub const OPERATING_CLASSES: &[OperatingClass] = &[
+    OperatingClass::new(
+        81,
+        Band::TwoPointFourGHz,
+        ChannelBandwidth::TwentyMHz,
+        2407,
+        SupportChannel::Primary,
+        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+    ),
+    OperatingClass::new(
+        82,
+        Band::TwoPointFourGHz,
+        ChannelBandwidth::TwentyMHz,
+        2414,
+        SupportChannel::Primary,
+        &[14],
+    ),
+    OperatingClass::new(
+        83,
+        Band::TwoPointFourGHz,
+        ChannelBandwidth::FourtyMHz,
+        2407,
+        SupportChannel::Lower,
+        &[1, 2, 3, 4, 5, 6, 7, 8, 9],
+    ),
+    OperatingClass::new(
+        84,
+        Band::TwoPointFourGHz,
+        ChannelBandwidth::FourtyMHz,
+        2407,
+        SupportChannel::Upper,
+        &[5, 6, 7, 8, 9, 10, 11, 12, 13],
+    ),
+    OperatingClass::new(
+        115,
+        Band::FiveGHz,
+        ChannelBandwidth::TwentyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[36, 40, 44, 48],
+    ),
+    OperatingClass::new(
+        116,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Lower,
+        &[36, 44],
+    ),
+    OperatingClass::new(
+        117,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Upper,
+        &[40, 48],
+    ),
+    OperatingClass::new(
+        118,
+        Band::FiveGHz,
+        ChannelBandwidth::TwentyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[52, 56, 60, 64],
+    ),
+    OperatingClass::new(
+        119,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Lower,
+        &[52, 60],
+    ),
+    OperatingClass::new(
+        120,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Upper,
+        &[56, 64],
+    ),
+    OperatingClass::new(
+        121,
+        Band::FiveGHz,
+        ChannelBandwidth::TwentyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140, 144],
+    ),
+    OperatingClass::new(
+        122,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Lower,
+        &[100, 108, 116, 124, 132, 140],
+    ),
+    OperatingClass::new(
+        123,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Upper,
+        &[104, 112, 120, 128, 136, 144],
+    ),
+    OperatingClass::new(
+        124,
+        Band::FiveGHz,
+        ChannelBandwidth::TwentyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[149, 153, 157, 161],
+    ),
+    OperatingClass::new(
+        125,
+        Band::FiveGHz,
+        ChannelBandwidth::TwentyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[149, 153, 157, 161, 165, 169, 173, 177],
+    ),
+    OperatingClass::new(
+        126,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Lower,
+        &[149, 157, 165, 173],
+    ),
+    OperatingClass::new(
+        127,
+        Band::FiveGHz,
+        ChannelBandwidth::FourtyMHz,
+        5000,
+        SupportChannel::Upper,
+        &[153, 161, 169, 177],
+    ),
+    OperatingClass::new(
+        128,
+        Band::FiveGHz,
+        ChannelBandwidth::EightyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[42, 58, 106, 122, 138, 155, 171],
+    ),
+    OperatingClass::new(
+        129,
+        Band::FiveGHz,
+        ChannelBandwidth::OneHundredSixtyMHz,
+        5000,
+        SupportChannel::Primary,
+        &[50, 114, 163],
+    ),
+    OperatingClass::new(
+        131,
+        Band::SixGHz,
+        ChannelBandwidth::TwentyMHz,
+        5950,
+        SupportChannel::Primary,
+        &[
+            1, 5, 9, 13, 17, 21, 25, 29, 33, 37, 41, 45, 49, 53, 57, 61, 65, 69, 73, 77, 81, 85,
+            89, 93, 97, 101, 105, 109, 113, 117, 121, 125, 129, 133, 137, 141, 145, 149, 153, 157,
+            161, 165, 169, 173, 177, 181, 185, 189, 193, 197, 201, 205, 209, 213, 217, 221, 225,
+            229, 233,
+        ],
+    ),
+    OperatingClass::new(
+        132,
+        Band::SixGHz,
+        ChannelBandwidth::FourtyMHz,
+        5950,
+        SupportChannel::Primary,
+        &[
+            3, 11, 19, 27, 35, 43, 51, 59, 67, 75, 83, 91, 99, 107, 115, 123, 131, 139, 147, 155,
+            163, 171, 179, 187, 195, 203, 211, 219, 227,
+        ],
+    ),
+    OperatingClass::new(
+        133,
+        Band::SixGHz,
+        ChannelBandwidth::EightyMHz,
+        5950,
+        SupportChannel::Primary,
+        &[
+            7, 23, 39, 55, 71, 87, 103, 119, 135, 151, 167, 183, 199, 215,
+        ],
+    ),
+    OperatingClass::new(
+        134,
+        Band::SixGHz,
+        ChannelBandwidth::OneHundredSixtyMHz,
+        5950,
+        SupportChannel::Primary,
+        &[15, 47, 79, 111, 143, 175, 207],
+    ),
+    OperatingClass::new(
+        136,
+        Band::SixGHz,
+        ChannelBandwidth::TwentyMHz,
+        5925,
+        SupportChannel::Primary,
+        &[2],
+    ),
+];
+#[cfg(test)]
+#[test]
+fn test_operating_classes() {
+    let operating_class = OperatingClass::from_opclass(128).unwrap();
+    assert_eq!(operating_class.center_channel(44), Some(42));
+    assert_eq!(operating_class.center_channel(149), Some(155));
+    assert_eq!(operating_class.center_channel(50), None);
+    assert_eq!(operating_class.frequency(42), 5210);
+
+    let operating_class = OperatingClass::from_opclass(83).unwrap();
+    assert_eq!(operating_class.center_channel(1), Some(3));
+    assert_eq!(operating_class.frequency(3), 2422);
+    assert_eq!(
+        OperatingClass::from_opclass(82).unwrap().frequency(14),
+        2484
+    );
+    assert_eq!(
+        OperatingClass::find(
+            Band::FiveGHz,
+            ChannelBandwidth::FourtyMHz,
+            SupportChannel::Upper,
+            104
+        )
+        .map(|operating_class| operating_class.opclass),
+        Some(123)
+    );
+    assert_eq!(operating_class.channel(2422), Some(3));
+    assert_eq!(operating_class.channel(2423), None);
+    assert_eq!(
+        OperatingClass::from_opclass(129)
+            .unwrap()
+            .support_channel(44),
+        Some(SupportChannel::Lower)
+    );
+    assert!(OperatingClass::from_opclass(130).is_none());
+}
+#[cfg(test)]
+#[test]
+fn test_six_ghz_and_160mhz_operating_classes() {
+    use alloc::vec;
+    use core::num::NonZeroU8;
+
+    use scroll::{ctx::MeasureWith, Pread, Pwrite};
+
+    use super::{
+        channel::{Channel, ChannelEncoding},
+        channel_sequence::ChannelSequence,
+        ChannelSequenceTLV,
+    };
+
+    for opclass in [129, 131, 132, 133, 134, 136] {
+        let operating_class = OperatingClass::from_opclass(opclass).unwrap();
+        let primary_channels = (0..=u8::MAX).filter(|channel| operating_class.contains(*channel));
+        // Every center channel of a wider channel contains one primary channel per 20MHz.
+        let channels_per_entry = match operating_class.primary_position {
+            SupportChannel::Primary => operating_class.bandwidth.mhz().unwrap() as usize / 20,
+            _ => 1,
+        };
+        assert_eq!(
+            primary_channels.clone().count(),
+            operating_class.channel_set.len() * channels_per_entry
+        );
+
+        for primary_channel in primary_channels {
+            let primary_frequency = operating_class.frequency(primary_channel);
+            let channel = Channel::from_frequency(
+                primary_frequency,
+                operating_class.bandwidth,
+                ChannelEncoding::OpClass,
+            )
+            .unwrap();
+            assert_eq!(
+                channel.operating_class().map(|op_class| op_class.opclass),
+                Some(opclass)
+            );
+            assert_eq!(channel.band(), Some(operating_class.band));
+            assert_eq!(channel.bandwidth(), Some(operating_class.bandwidth));
+            assert_eq!(channel.primary_frequency(), Some(primary_frequency));
+            assert_eq!(
+                channel.center_frequency(),
+                operating_class
+                    .center_channel(primary_channel)
+                    .map(|center_channel| operating_class.frequency(center_channel))
+            );
+
+            let channel_sequence_tlv = ChannelSequenceTLV::<16> {
+                step_count: NonZeroU8::new(4).unwrap(),
+                duplicate_count: 0,
+                channel_sequence: ChannelSequence::fixed_channel_sequence(channel),
+            };
+            let mut buf = vec![0x00; channel_sequence_tlv.measure_with(&())];
+            buf.pwrite(channel_sequence_tlv.clone(), 0).unwrap();
+            assert_eq!(
+                buf.pread::<ChannelSequenceTLV>(0).unwrap(),
+                channel_sequence_tlv
+            );
+            for channel_encoding in [ChannelEncoding::Simple, ChannelEncoding::Legacy] {
+                assert!(Channel::from_frequency(
+                    primary_frequency,
+                    operating_class.bandwidth,
+                    channel_encoding
+                )
+                .is_none());
+            }
+        }
+    }
+}
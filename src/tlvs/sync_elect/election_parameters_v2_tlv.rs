@@ -93,6 +93,76 @@ impl TryIntoCtx for ElectionParametersV2TLV {
         Ok(offset)
     }
 }
+/// A builder for the [ElectionParametersV2TLV].
+///
+/// The defaults were taken from a MIF sent by macOS.
+pub struct ElectionParametersV2TLVBuilder {
+    tlv: ElectionParametersV2TLV,
+}
+impl ElectionParametersV2TLVBuilder {
+    /// Create a new builder with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    builder_setters!(
+        master_address: MACAddress,
+        sync_address: MACAddress,
+        master_counter: u32,
+        distance_to_master: u32,
+        master_metric: u32,
+        self_metric: u32,
+        election_id: u32,
+        self_counter: u32
+    );
+    /// Make the peer the master of the mesh.
+    ///
+    /// This sets the master and sync address to the address of the peer and copies the self metric and counter to the master metric and counter.
+    pub fn master(mut self, address: MACAddress) -> Self {
+        self.tlv.master_address = address;
+        self.tlv.sync_address = address;
+        self.tlv.distance_to_master = 0;
+        self.tlv.master_metric = self.tlv.self_metric;
+        self.tlv.master_counter = self.tlv.self_counter;
+        self
+    }
+    /// Validate the parameters and return the TLV.
+    pub fn build(self) -> Result<ElectionParametersV2TLV, scroll::Error> {
+        let tlv = self.tlv;
+        if tlv.self_metric > tlv.master_metric {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Self metric exceeds the master metric.",
+            });
+        }
+        if tlv.distance_to_master == 0
+            && (tlv.master_address != tlv.sync_address
+                || tlv.master_metric != tlv.self_metric
+                || tlv.master_counter != tlv.self_counter)
+        {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "A master must sync to itself.",
+            });
+        }
+        Ok(tlv)
+    }
+}
+impl Default for ElectionParametersV2TLVBuilder {
+    fn default() -> Self {
+        Self {
+            tlv: ElectionParametersV2TLV {
+                master_address: MACAddress::default(),
+                sync_address: MACAddress::default(),
+                master_counter: 10,
+                distance_to_master: 1,
+                master_metric: 541,
+                self_metric: 535,
+                election_id: 0,
+                self_counter: 25,
+            },
+        }
+    }
+}
 #[cfg(test)]
 #[test]
 fn test_election_parameters_v2_tlv() {
@@ -119,3 +189,35 @@ fn test_election_parameters_v2_tlv() {
 
     assert_eq!(buf, bytes);
 }
+#[cfg(test)]
+#[test]
+fn test_election_parameters_v2_tlv_builder() {
+    let address = MACAddress::new([0xce, 0x21, 0x1f, 0x62, 0x21, 0x22]);
+    let election_parameters_v2_tlv = ElectionParametersV2TLVBuilder::new()
+        .self_metric(650)
+        .self_counter(30)
+        .master(address)
+        .build()
+        .unwrap();
+    assert_eq!(
+        election_parameters_v2_tlv,
+        ElectionParametersV2TLV {
+            master_address: address,
+            sync_address: address,
+            master_counter: 30,
+            distance_to_master: 0,
+            master_metric: 650,
+            self_metric: 650,
+            election_id: 0,
+            self_counter: 30,
+        }
+    );
+    assert!(ElectionParametersV2TLVBuilder::new()
+        .self_metric(1000)
+        .build()
+        .is_err());
+    assert!(ElectionParametersV2TLVBuilder::new()
+        .distance_to_master(0)
+        .build()
+        .is_err());
+}
@@ -1,3 +1,5 @@
+use core::num::NonZeroU8;
+
 use mac_parser::MACAddress;
 use scroll::{
     ctx::{MeasureWith, TryFromCtx, TryIntoCtx},
@@ -6,7 +8,7 @@ use scroll::{
 
 use crate::tlvs::{AWDLTLVType, AwdlTlv};
 
-use super::ChannelSequenceTLV;
+use super::{channel::Channel, channel_sequence::ChannelSequence, ChannelSequenceTLV};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 /// The synchronization parameters of the peer.
@@ -122,16 +124,107 @@ impl TryIntoCtx for SynchronizationParametersTLV {
         Ok(offset)
     }
 }
+/// A builder for the [SynchronizationParametersTLV].
+///
+/// The defaults were taken from a MIF sent by macOS.
+pub struct SynchronizationParametersTLVBuilder {
+    tlv: SynchronizationParametersTLV,
+}
+impl SynchronizationParametersTLVBuilder {
+    /// Create a new builder with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    builder_setters!(
+        next_channel: u8,
+        tx_counter: u16,
+        master_channel: u8,
+        guard_time: u8,
+        aw_period: u16,
+        af_period: u16,
+        awdl_flags: u16,
+        aw_ext_length: u16,
+        aw_common_length: u16,
+        remaining_aw_length: u16,
+        min_ext_count: u8,
+        max_multicast_ext_count: u8,
+        max_unicast_ext_count: u8,
+        max_af_ext_count: u8,
+        master_address: MACAddress,
+        presence_mode: u8,
+        aw_seq_number: u16,
+        ap_beacon_alignment_delta: u16,
+        channel_sequence: ChannelSequenceTLV
+    );
+    /// Validate the parameters and return the TLV.
+    pub fn build(self) -> Result<SynchronizationParametersTLV, scroll::Error> {
+        let tlv = self.tlv;
+        if tlv.aw_period == 0 || tlv.af_period == 0 {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "AW and AF period can't be zero.",
+            });
+        }
+        if tlv.remaining_aw_length > tlv.aw_period {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Remaining AW length exceeds the AW period.",
+            });
+        }
+        if tlv.min_ext_count > tlv.max_multicast_ext_count
+            || tlv.min_ext_count > tlv.max_unicast_ext_count
+            || tlv.min_ext_count > tlv.max_af_ext_count
+        {
+            return Err(scroll::Error::BadInput {
+                size: 0,
+                msg: "Minimum extension count exceeds a maximum extension count.",
+            });
+        }
+        Ok(tlv)
+    }
+}
+impl Default for SynchronizationParametersTLVBuilder {
+    fn default() -> Self {
+        Self {
+            tlv: SynchronizationParametersTLV {
+                next_channel: 6,
+                tx_counter: 0,
+                master_channel: 6,
+                guard_time: 0,
+                aw_period: 16,
+                af_period: 110,
+                awdl_flags: 0x1800,
+                aw_ext_length: 16,
+                aw_common_length: 16,
+                remaining_aw_length: 0,
+                min_ext_count: 3,
+                max_multicast_ext_count: 3,
+                max_unicast_ext_count: 3,
+                max_af_ext_count: 3,
+                master_address: MACAddress::default(),
+                presence_mode: 4,
+                aw_seq_number: 0,
+                ap_beacon_alignment_delta: 0,
+                channel_sequence: ChannelSequenceTLV {
+                    step_count: NonZeroU8::new(4).unwrap(),
+                    duplicate_count: 0,
+                    channel_sequence: ChannelSequence::fixed_channel_sequence(Channel::OpClass {
+                        channel: 6,
+                        opclass: 0x51,
+                    }),
+                },
+            },
+        }
+    }
+}
 #[cfg(test)]
 #[test]
 fn test_sync_parameters_tlv() {
-    use core::num::NonZeroU8;
-
     use alloc::vec;
 
     use crate::tlvs::sync_elect::{
         channel::{Band, ChannelBandwidth, LegacyFlags, SupportChannel},
-        channel_sequence::ChannelSequence,
+        channel_sequence::{ChannelArray, ChannelSequence},
     };
 
     let bytes = &include_bytes!("../../../test_bins/sync_parameters_tlv.bin")[3..];
@@ -160,136 +253,140 @@ fn test_sync_parameters_tlv() {
             ap_beacon_alignment_delta: 1986,
             channel_sequence: ChannelSequenceTLV {
                 step_count: NonZeroU8::new(4).unwrap(),
-                channel_sequence: ChannelSequence::Legacy([
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Primary,
-                            channel_bandwidth: ChannelBandwidth::FourtyMHz,
-                            band: Band::TwoPointFourGHz
-                        },
-                        8
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Primary,
-                            channel_bandwidth: ChannelBandwidth::FourtyMHz,
-                            band: Band::TwoPointFourGHz
-                        },
-                        8
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Primary,
-                            channel_bandwidth: ChannelBandwidth::FourtyMHz,
-                            band: Band::TwoPointFourGHz
-                        },
-                        8
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Primary,
-                            channel_bandwidth: ChannelBandwidth::FourtyMHz,
-                            band: Band::TwoPointFourGHz
-                        },
-                        8
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        46
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        38
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        38
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        38
-                    ),
-                    (
-                        LegacyFlags {
-                            support_channel: SupportChannel::Lower,
-                            channel_bandwidth: ChannelBandwidth::EightyMHz,
-                            band: Band::FiveGHz
-                        },
-                        38
-                    ),
-                ])
+                duplicate_count: 0,
+                channel_sequence: ChannelSequence::Legacy(
+                    ChannelArray::from_slice(&[
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Primary,
+                                channel_bandwidth: ChannelBandwidth::FourtyMHz,
+                                band: Band::TwoPointFourGHz
+                            },
+                            8
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Primary,
+                                channel_bandwidth: ChannelBandwidth::FourtyMHz,
+                                band: Band::TwoPointFourGHz
+                            },
+                            8
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Primary,
+                                channel_bandwidth: ChannelBandwidth::FourtyMHz,
+                                band: Band::TwoPointFourGHz
+                            },
+                            8
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Primary,
+                                channel_bandwidth: ChannelBandwidth::FourtyMHz,
+                                band: Band::TwoPointFourGHz
+                            },
+                            8
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            46
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            38
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            38
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            38
+                        ),
+                        (
+                            LegacyFlags {
+                                support_channel: SupportChannel::Lower,
+                                channel_bandwidth: ChannelBandwidth::EightyMHz,
+                                band: Band::FiveGHz
+                            },
+                            38
+                        ),
+                    ])
+                    .unwrap()
+                )
             }
         }
     );
@@ -297,3 +394,24 @@ fn test_sync_parameters_tlv() {
     buf.as_mut_slice().pwrite(sync_parameters_tlv, 0).unwrap();
     assert_eq!(buf, bytes);
 }
+#[cfg(test)]
+#[test]
+fn test_sync_parameters_tlv_builder() {
+    let sync_parameters_tlv = SynchronizationParametersTLVBuilder::new()
+        .tx_counter(49)
+        .master_channel(44)
+        .build()
+        .unwrap();
+    assert_eq!(sync_parameters_tlv.tx_counter, 49);
+    assert_eq!(sync_parameters_tlv.master_channel, 44);
+    assert_eq!(sync_parameters_tlv.aw_period, 16);
+
+    assert!(SynchronizationParametersTLVBuilder::new()
+        .aw_period(0)
+        .build()
+        .is_err());
+    assert!(SynchronizationParametersTLVBuilder::new()
+        .min_ext_count(4)
+        .build()
+        .is_err());
+}
@@ -0,0 +1,128 @@
+use scroll::{ctx::TryFromCtx, Pread};
+
+use super::{AWDLTLVType, AwdlTlv, RawAWDLTLV, ReadTLVs};
+
+/// The amount of TLV types, which are indexed by the [TLVIndex].
+const INDEXED_TLV_TYPE_COUNT: usize = 11;
+
+impl AWDLTLVType {
+    #[inline]
+    /// Returns the slot of the TLV type in the [TLVIndex].
+    const fn index_slot(&self) -> Option<usize> {
+        Some(match self {
+            Self::ServiceResponse => 0,
+            Self::SynchronizationParameters => 1,
+            Self::ElectionParameters => 2,
+            Self::HTCapabilities => 3,
+            Self::DataPathState => 4,
+            Self::Arpa => 5,
+            Self::IEEE80211Container => 6,
+            Self::ChannelSequence => 7,
+            Self::SynchronizationTree => 8,
+            Self::Version => 9,
+            Self::ElectionParametersV2 => 10,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// An index of the TLVs in an action frame, which is created in one pass by [ReadTLVs::index](super::ReadTLVs::index).
+///
+/// The offset of the first TLV of every known type is stored, so lookups don't have to rescan the preceding TLVs.
+pub struct TLVIndex<'a> {
+    bytes: &'a [u8],
+    offsets: [Option<usize>; INDEXED_TLV_TYPE_COUNT],
+}
+impl<'a> TLVIndex<'a> {
+    /// Scan the TLVs once and record the offset of the first TLV of every known type.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let mut offsets = [None; INDEXED_TLV_TYPE_COUNT];
+        let mut offset = 0;
+        loop {
+            let tlv_offset = offset;
+            let Ok(raw_tlv) = bytes.gread::<RawAWDLTLV>(&mut offset) else {
+                break;
+            };
+            if let Some(slot) = AWDLTLVType::from_bits(raw_tlv.tlv_type).index_slot() {
+                offsets[slot].get_or_insert(tlv_offset);
+            }
+        }
+        Self { bytes, offsets }
+    }
+    /// Returns the offset of the first TLV of that type.
+    const fn offset(&self, tlv_type: AWDLTLVType) -> Option<usize> {
+        match tlv_type.index_slot() {
+            Some(slot) => self.offsets[slot],
+            None => None,
+        }
+    }
+    /// Check if a TLV of that type is present.
+    pub const fn contains(&self, tlv_type: AWDLTLVType) -> bool {
+        self.offset(tlv_type).is_some()
+    }
+    /// Get the payload of the first TLV of that type.
+    pub fn get_payload(&self, tlv_type: AWDLTLVType) -> Option<&'a [u8]> {
+        self.bytes
+            .pread::<RawAWDLTLV>(self.offset(tlv_type)?)
+            .ok()
+            .map(|raw_tlv| raw_tlv.slice)
+    }
+    /// Get the first matching TLV.
+    ///
+    /// Like [ReadTLVs::get_first_tlv](super::ReadTLVs::get_first_tlv), TLVs which fail to parse are skipped.
+    pub fn get_first_tlv<Tlv: AwdlTlv + TryFromCtx<'a, Error = scroll::Error>>(
+        &self,
+    ) -> Option<Tlv> {
+        ReadTLVs::new(&self.bytes[self.offset(Tlv::TLV_TYPE)?..]).get_first_tlv()
+    }
+}
+#[cfg(test)]
+#[test]
+fn test_tlv_index() {
+    use crate::{
+        action_frame::DefaultAWDLActionFrame,
+        tlvs::{
+            data_path::DataPathStateTLV, dns_sd::DefaultArpaTLV,
+            sync_elect::SynchronizationParametersTLV, version::VersionTLV,
+        },
+    };
+
+    let bytes = include_bytes!("../../test_bins/mif.bin");
+    let tlvs = bytes
+        .pread::<DefaultAWDLActionFrame>(0)
+        .unwrap()
+        .tagged_data;
+    let index = tlvs.index();
+
+    assert!(index.contains(AWDLTLVType::Version));
+    assert!(!index.contains(AWDLTLVType::SynchronizationTree));
+    assert!(!index.contains(AWDLTLVType::Unknown(0x06)));
+    assert_eq!(
+        index.get_first_tlv::<VersionTLV>(),
+        tlvs.get_first_tlv::<VersionTLV>()
+    );
+    assert_eq!(
+        index.get_first_tlv::<DataPathStateTLV>(),
+        tlvs.get_first_tlv::<DataPathStateTLV>()
+    );
+    assert_eq!(
+        index.get_first_tlv::<SynchronizationParametersTLV>(),
+        tlvs.get_first_tlv::<SynchronizationParametersTLV>()
+    );
+    assert_eq!(
+        index.get_first_tlv::<DefaultArpaTLV>(),
+        tlvs.get_first_tlv::<DefaultArpaTLV>()
+    );
+
+    // A malformed first TLV falls through to the next one, like in ReadTLVs.
+    let bytes = [0x15, 0x01, 0x00, 0x3e, 0x15, 0x02, 0x00, 0x3e, 0x01];
+    let tlvs = ReadTLVs::new(&bytes);
+    let index = tlvs.index();
+    assert_eq!(index.get_payload(AWDLTLVType::Version), Some(&bytes[3..4]));
+    assert_eq!(
+        index.get_first_tlv::<VersionTLV>(),
+        tlvs.get_first_tlv::<VersionTLV>()
+    );
+    assert!(index.get_first_tlv::<VersionTLV>().is_some());
+}